@@ -1,4 +1,39 @@
 use advent_of_code_template::{day01::Day01, solve_day, Solution};
+use itertools::Itertools;
+
+/// The lists as they used to be parsed, left in input order.
+fn parse_unsorted(input_lines: &str) -> [Vec<u32>; 2] {
+    let mut list1 = Vec::new();
+    let mut list2 = Vec::new();
+    for line in input_lines.lines() {
+        let mut parts = line.split_whitespace();
+        list1.push(parts.next().unwrap().parse().unwrap());
+        list2.push(parts.next().unwrap().parse().unwrap());
+    }
+    [list1, list2]
+}
+
+/// Part one sorting both lists itself, since the parser used not to.
+fn part_one_sorting(lists: &[Vec<u32>; 2]) -> u32 {
+    lists[0]
+        .iter()
+        .sorted()
+        .zip(lists[1].iter().sorted())
+        .fold(0, |sum, (list1_item, list2_item)| {
+            sum + list1_item.abs_diff(*list2_item)
+        })
+}
+
+/// Part two rescanning the whole second list for each item of the first.
+fn part_two_rescanning(lists: &[Vec<u32>; 2]) -> u32 {
+    lists[0].iter().fold(0, |sum, list1_item| {
+        sum + lists[1]
+            .iter()
+            .filter(|list2_item| *list2_item == list1_item)
+            .sum::<u32>()
+    })
+}
+
 fn parsing() {
     Day01::parse_input(include_str!("../inputs/1"));
 }
@@ -7,10 +42,18 @@ fn parsing_and_part_one() {
     Day01::solve_part_one(include_str!("../inputs/1"));
 }
 
+fn parsing_and_part_one_sorting() {
+    part_one_sorting(&parse_unsorted(include_str!("../inputs/1")));
+}
+
 fn parsing_and_part_two() {
     Day01::solve_part_two(include_str!("../inputs/1"));
 }
 
+fn parsing_and_part_two_rescanning() {
+    part_two_rescanning(&parse_unsorted(include_str!("../inputs/1")));
+}
+
 fn whole_solution() {
     solve_day(&1, false)
 }
@@ -18,6 +61,8 @@ fn whole_solution() {
 iai::main!(
     parsing,
     parsing_and_part_one,
+    parsing_and_part_one_sorting,
     parsing_and_part_two,
+    parsing_and_part_two_rescanning,
     whole_solution,
 );
@@ -1,4 +1,11 @@
-use advent_of_code_template::{day06::Day06, solve_day, Solution};
+use std::collections::HashSet;
+
+use advent_of_code_template::{
+    cycle,
+    day06::{Day06, MapElement},
+    grid::{Direction, Grid},
+    solve_day, Solution,
+};
 fn parsing() {
     Day06::parse_input(include_str!("../inputs/6"));
 }
@@ -11,6 +18,53 @@ fn parsing_and_part_two() {
     Day06::solve_part_two(include_str!("../inputs/6"));
 }
 
+/// Part two as it was before the jump table: every candidate obstacle
+/// re-runs the patrol from the guard's starting position, remembering every
+/// state it passes through in a hash set.
+fn part_two_from_start(grid: &mut Grid<MapElement>) -> usize {
+    let (row, col, guard) = grid
+        .locate(|element| matches!(element, MapElement::Guard(..)))
+        .expect("Could not find the guard");
+    let initial_direction = match guard {
+        MapElement::Guard(direction) => *direction,
+        _ => unreachable!("Guard is not a guard"),
+    };
+    let is_looped = |grid: &Grid<MapElement>| {
+        cycle::hashed(
+            (row, col, initial_direction),
+            |&(i, j, direction): &(usize, usize, Direction)| match grid.walk(i, j, &direction) {
+                None => None,
+                Some((_, _, MapElement::Obstacle)) => Some((i, j, direction.turn_right())),
+                Some((next_i, next_j, _)) => Some((next_i, next_j, direction)),
+            },
+        )
+        .is_some()
+    };
+
+    let mut direction = initial_direction;
+    let mut potential_obstacles: HashSet<(usize, usize)> = HashSet::new();
+    let (mut i, mut j) = (row, col);
+    loop {
+        match grid.walk(i, j, &direction) {
+            None => break,
+            Some((_, _, MapElement::Obstacle)) => direction = direction.turn_right(),
+            Some((next_i, next_j, _)) => {
+                let previous = std::mem::replace(&mut grid[(next_i, next_j)], MapElement::Obstacle);
+                if is_looped(grid) {
+                    potential_obstacles.insert((next_i, next_j));
+                }
+                grid[(next_i, next_j)] = previous;
+                (i, j) = (next_i, next_j);
+            }
+        }
+    }
+    potential_obstacles.len()
+}
+
+fn parsing_and_part_two_from_start() {
+    part_two_from_start(&mut Day06::parse_input(include_str!("../inputs/6")));
+}
+
 fn whole_solution() {
     solve_day(&6, false)
 }
@@ -19,5 +73,6 @@ iai::main!(
     parsing,
     parsing_and_part_one,
     parsing_and_part_two,
+    parsing_and_part_two_from_start,
     whole_solution,
 );
@@ -1,4 +1,7 @@
-use advent_of_code_template::{day09::Day09, solve_day, Solution};
+use advent_of_code_template::{
+    day09::{Block, Day09},
+    solve_day, Solution,
+};
 fn parsing() {
     Day09::parse_input(include_str!("../inputs/9"));
 }
@@ -7,6 +10,41 @@ fn parsing_and_part_one() {
     Day09::solve_part_one(include_str!("../inputs/9"));
 }
 
+/// Part one before the disk was kept as spans: swaps the last file block into
+/// the first free one until nothing is left to move, rescanning the whole disk
+/// after every swap.
+fn part_one_block_by_block(disk: &mut [Block]) -> u64 {
+    let file_blocks = disk.iter().filter(|block| **block != Block::Free).count();
+    let is_compacted = |disk: &[Block]| {
+        disk.iter()
+            .position(|block| *block == Block::Free)
+            .is_none_or(|idx| idx == file_blocks)
+    };
+    while !is_compacted(disk) {
+        let first_empty_block = disk.iter().position(|block| *block == Block::Free).unwrap();
+        let last_file_block = disk
+            .iter()
+            .rposition(|block| *block != Block::Free)
+            .unwrap();
+        disk.swap(first_empty_block, last_file_block);
+    }
+    disk.iter()
+        .filter_map(|block| match block {
+            Block::File(id) => Some(id),
+            Block::Free => None,
+        })
+        .enumerate()
+        .fold(0, |acc, (idx, id)| acc + idx as u64 * *id)
+}
+
+fn parsing_and_part_one_block_by_block() {
+    part_one_block_by_block(
+        &mut Day09::parse_input(include_str!("../inputs/9"))
+            .blocks()
+            .collect::<Vec<_>>(),
+    );
+}
+
 fn parsing_and_part_two() {
     Day09::solve_part_two(include_str!("../inputs/9"));
 }
@@ -18,6 +56,7 @@ fn whole_solution() {
 iai::main!(
     parsing,
     parsing_and_part_one,
+    parsing_and_part_one_block_by_block,
     parsing_and_part_two,
     whole_solution,
 );
@@ -0,0 +1,165 @@
+//! Derive macro for `advent_of_code_template::grid::GridCell`, mapping the
+//! variants of an enum to and from the characters of a puzzle grid.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitChar, Type};
+
+/// Derives `GridCell`, along with `Display`, `FromStr` and `TryFrom<char>`
+/// implementations which agree with it.
+///
+/// Every unit variant must be annotated with the character it is drawn as,
+/// e.g. `#[cell('#')]`. A variant with a single unnamed field and no attribute,
+/// e.g. `Guard(Direction)`, is drawn as its payload, which must itself
+/// implement `GridCell`; when parsing, unit variants are tried first, then
+/// payload variants in declaration order.
+#[proc_macro_derive(GridCell, attributes(cell))]
+pub fn derive_grid_cell(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+enum CellVariant<'a> {
+    Unit(&'a syn::Ident, LitChar),
+    Payload(&'a syn::Ident, &'a Type),
+}
+
+fn cell_character(variant: &syn::Variant) -> syn::Result<Option<LitChar>> {
+    let mut found = None;
+    for attribute in variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cell"))
+    {
+        if found.is_some() {
+            return Err(Error::new_spanned(attribute, "duplicate #[cell] attribute"));
+        }
+        found = Some(attribute.parse_args::<LitChar>()?);
+    }
+    Ok(found)
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "GridCell can only be derived for enums",
+        ));
+    };
+
+    let mut variants = vec![];
+    for variant in &data.variants {
+        let character = cell_character(variant)?;
+        match (&variant.fields, character) {
+            (Fields::Unit, Some(character)) => {
+                if let Some(CellVariant::Unit(_, previous)) = variants.iter().find(|other| {
+                    matches!(other, CellVariant::Unit(_, c) if c.value() == character.value())
+                }) {
+                    let mut error =
+                        Error::new_spanned(&character, "character is used by another variant");
+                    error.combine(Error::new_spanned(previous, "first used here"));
+                    return Err(error);
+                }
+                variants.push(CellVariant::Unit(&variant.ident, character));
+            }
+            (Fields::Unit, None) => {
+                return Err(Error::new_spanned(
+                    variant,
+                    "unit variants need a #[cell('x')] attribute",
+                ))
+            }
+            (Fields::Unnamed(fields), None) if fields.unnamed.len() == 1 => {
+                variants.push(CellVariant::Payload(&variant.ident, &fields.unnamed[0].ty));
+            }
+            (_, Some(character)) => {
+                return Err(Error::new_spanned(
+                    character,
+                    "#[cell] can only be used on unit variants",
+                ))
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    variant,
+                    "variants with data must have exactly one unnamed field, drawn as a GridCell",
+                ))
+            }
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let krate = quote!(::advent_of_code_template::grid);
+
+    let unit_parses = variants.iter().filter_map(|variant| match variant {
+        CellVariant::Unit(ident, character) => Some(quote!(#character => Some(Self::#ident),)),
+        CellVariant::Payload(..) => None,
+    });
+    let payload_parses = variants.iter().filter_map(|variant| match variant {
+        CellVariant::Payload(ident, ty) => Some(quote! {
+            if let Some(inner) = <#ty as #krate::GridCell>::from_char(character) {
+                return Some(Self::#ident(inner));
+            }
+        }),
+        CellVariant::Unit(..) => None,
+    });
+    let renders = variants.iter().map(|variant| match variant {
+        CellVariant::Unit(ident, character) => quote!(Self::#ident => #character,),
+        CellVariant::Payload(ident, ty) => {
+            quote!(Self::#ident(inner) => <#ty as #krate::GridCell>::to_char(inner),)
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #krate::GridCell for #name #type_generics #where_clause {
+            fn from_char(character: char) -> Option<Self> {
+                #[allow(clippy::match_single_binding)]
+                let unit = match character {
+                    #(#unit_parses)*
+                    _ => None,
+                };
+                if unit.is_some() {
+                    return unit;
+                }
+                #(#payload_parses)*
+                None
+            }
+
+            fn to_char(&self) -> char {
+                match self {
+                    #(#renders)*
+                }
+            }
+        }
+
+        impl #impl_generics ::std::fmt::Display for #name #type_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::Write::write_char(f, #krate::GridCell::to_char(self))
+            }
+        }
+
+        impl #impl_generics ::std::str::FromStr for #name #type_generics #where_clause {
+            type Err = #krate::ParseCellError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let mut characters = s.chars();
+                match (characters.next(), characters.next()) {
+                    (Some(character), None) => <Self as #krate::GridCell>::from_char(character),
+                    _ => None,
+                }
+                .ok_or_else(|| #krate::ParseCellError(s.to_string()))
+            }
+        }
+
+        impl #impl_generics ::std::convert::TryFrom<char> for #name #type_generics #where_clause {
+            type Error = #krate::ParseCellError;
+
+            fn try_from(character: char) -> Result<Self, Self::Error> {
+                <Self as #krate::GridCell>::from_char(character)
+                    .ok_or_else(|| #krate::ParseCellError(character.to_string()))
+            }
+        }
+    })
+}
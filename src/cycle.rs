@@ -0,0 +1,187 @@
+//! Cycle detection for sequences of states produced by repeatedly applying a
+//! step function, as in simulations which eventually repeat themselves.
+//!
+//! Step functions return `None` when the sequence ends (e.g. a guard walks
+//! off the map), in which case there is no cycle. For sequences which never
+//! end, just wrap each next state in `Some`.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Where a sequence of states starts repeating: the state at index `start`
+/// (counting the initial state as index zero) is the first to recur, and does
+/// so every `length` steps.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+/// Floyd's tortoise and hare algorithm. Needs only two states in memory at a
+/// time, at the cost of stepping each state several times.
+pub fn floyd<S, F>(initial: S, mut step: F) -> Option<Cycle>
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut tortoise = step(&initial)?;
+    let mut hare = step(&tortoise)?;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        let halfway = step(&hare)?;
+        hare = step(&halfway)?;
+    }
+
+    // The tortoise is now a whole number of cycles ahead of the start, so
+    // moving the tortoise back to the start and the two together meet where
+    // the cycle begins.
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise)?;
+    while tortoise != hare {
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    Some(Cycle { start, length })
+}
+
+/// Brent's algorithm. Also needs only two states in memory at a time, but
+/// usually takes fewer steps than [`floyd`].
+pub fn brent<S, F>(initial: S, mut step: F) -> Option<Cycle>
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    // Put the hare a cycle length ahead, then advance both until they meet
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare)?;
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    Some(Cycle { start, length })
+}
+
+/// Records the states seen so far in order, stopping when one recurs or the
+/// sequence ends. Each state is stepped only once.
+fn record<S, F>(initial: S, mut step: F, limit: usize) -> (Vec<S>, Option<Cycle>)
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut seen: HashMap<S, usize> = HashMap::from([(initial.clone(), 0)]);
+    let mut states = vec![initial];
+    while states.len() <= limit {
+        let Some(next) = step(states.last().unwrap()) else {
+            break;
+        };
+        if let Some(start) = seen.get(&next) {
+            let cycle = Cycle {
+                start: *start,
+                length: states.len() - start,
+            };
+            return (states, Some(cycle));
+        }
+        seen.insert(next.clone(), states.len());
+        states.push(next);
+    }
+    (states, None)
+}
+
+/// Detects a cycle by remembering every state seen. Steps each state only
+/// once, at the cost of keeping them all in memory.
+pub fn hashed<S, F>(initial: S, step: F) -> Option<Cycle>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> Option<S>,
+{
+    record(initial, step, usize::MAX).1
+}
+
+/// Finds the state after `steps` applications of the step function, skipping
+/// ahead once a cycle is found so that huge step counts are cheap. Returns
+/// `None` if the sequence ends first.
+pub fn state_after<S, F>(initial: S, step: F, steps: usize) -> Option<S>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> Option<S>,
+{
+    let (mut states, cycle) = record(initial, step, steps);
+    let index = match cycle {
+        Some(Cycle { start, length }) if steps >= start => start + (steps - start) % length,
+        _ => steps,
+    };
+    (index < states.len()).then(|| states.swap_remove(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0, 1, 2, 3, 4, 5, 6, 7, 3, 4, ...
+    fn rho(state: &u32) -> Option<u32> {
+        Some(if *state == 7 { 3 } else { state + 1 })
+    }
+
+    #[test]
+    fn check_detectors_agree() {
+        let expected = Some(Cycle {
+            start: 3,
+            length: 5,
+        });
+        assert_eq!(floyd(0, rho), expected);
+        assert_eq!(brent(0, rho), expected);
+        assert_eq!(hashed(0, rho), expected);
+        assert_eq!(
+            hashed(5, rho),
+            Some(Cycle {
+                start: 0,
+                length: 5
+            })
+        );
+    }
+
+    #[test]
+    fn check_terminating_sequence_has_no_cycle() {
+        let countdown = |state: &u32| state.checked_sub(1);
+        assert_eq!(floyd(10, countdown), None);
+        assert_eq!(brent(10, countdown), None);
+        assert_eq!(hashed(10, countdown), None);
+        assert_eq!(state_after(10, countdown, 4), Some(6));
+        assert_eq!(state_after(10, countdown, 11), None);
+    }
+
+    #[test]
+    fn check_state_after() {
+        assert_eq!(state_after(0, rho, 2), Some(2));
+        assert_eq!(state_after(0, rho, 8), Some(3));
+        assert_eq!(state_after(0, rho, 1_000_000_000), Some(5));
+    }
+}
@@ -6,6 +6,7 @@ use crate::Solution;
 pub struct Day01;
 
 impl Solution for Day01 {
+    /// Both lists, each sorted in ascending order.
     type ParsedInput = [Vec<u32>; 2];
 
     fn parse_input(input_lines: &str) -> Self::ParsedInput {
@@ -21,14 +22,15 @@ impl Solution for Day01 {
             list1.push(parts.next().unwrap().parse().unwrap());
             list2.push(parts.next().unwrap().parse().unwrap());
         }
+        list1.sort_unstable();
+        list2.sort_unstable();
         [list1, list2]
     }
 
     fn part_one(parsed_input: &mut Self::ParsedInput) -> String {
         parsed_input[0]
             .iter()
-            .sorted()
-            .zip(parsed_input[1].iter().sorted())
+            .zip(&parsed_input[1])
             .fold(0, |sum, (list1_item, list2_item)| {
                 sum + list1_item.abs_diff(*list2_item)
             })
@@ -36,14 +38,11 @@ impl Solution for Day01 {
     }
 
     fn part_two(parsed_input: &mut Self::ParsedInput) -> String {
+        let counts = parsed_input[1].iter().counts();
         parsed_input[0]
             .iter()
-            .fold(0, |sum, list1_item| {
-                sum + parsed_input[1]
-                    .iter()
-                    .filter(|list2_item| *list2_item == list1_item)
-                    .sum::<u32>()
-            })
+            .map(|list1_item| list1_item * counts.get(list1_item).copied().unwrap_or(0) as u32)
+            .sum::<u32>()
             .to_string()
     }
 }
@@ -82,6 +81,14 @@ mod tests {
         )
     }
 
+    #[test]
+    fn check_day01_parse_sorts_lists() {
+        assert_eq!(
+            Day01::parse_input("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n7   1"),
+            [vec![1, 2, 3, 3, 3, 4, 7], vec![1, 3, 3, 3, 4, 5, 9]]
+        );
+    }
+
     #[test]
     fn check_day01_both_case1() {
         assert_eq!(Day01::solve("", false), ("0".to_string(), "0".to_string()))
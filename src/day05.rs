@@ -1,3 +1,10 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    fmt::Display,
+};
+
 use itertools::Itertools;
 
 use crate::Solution;
@@ -11,25 +18,140 @@ pub struct Parsed {
     updates: Vec<Update>,
 }
 
+/// The page ordering rules, along with a precedence graph built from them
+/// once up front.
 #[derive(Debug, Default)]
-struct RuleSet {
+pub struct RuleSet {
     rules: Vec<Rule>,
+    /// The pages which must come after each page.
+    successors: HashMap<u32, Vec<u32>>,
+    /// Every (before, after) pair, for checking whether two pages are related
+    /// by a rule of their own.
+    pairs: HashSet<(u32, u32)>,
 }
 
 impl RuleSet {
-    fn rules_involving(&self, pages: &Vec<u32>) -> Vec<Rule> {
-        self.rules
+    pub fn new(rules: Vec<Rule>) -> Self {
+        let mut successors: HashMap<u32, Vec<u32>> = HashMap::new();
+        for rule in &rules {
+            successors.entry(rule.before).or_default().push(rule.after);
+        }
+        let pairs = rules.iter().map(|rule| (rule.before, rule.after)).collect();
+        Self {
+            rules,
+            successors,
+            pairs,
+        }
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    fn successors(&self, page: u32) -> &[u32] {
+        self.successors.get(&page).map_or(&[], Vec::as_slice)
+    }
+
+    /// Finds the single order the rules put the given pages in, following
+    /// rules through other pages among them. Kahn's algorithm gives one
+    /// order in a single pass, and it is the only one exactly when each page
+    /// has a rule putting it straight before the next.
+    pub fn order(&self, pages: &[u32]) -> Result<PageOrder, RuleSetError> {
+        let ordered = Update(pages.to_vec())
+            .with_rules(self)
+            .map_err(|CycleError { pages }| match pages[..] {
+                [first, second] => RuleSetError::Contradiction { first, second },
+                _ => RuleSetError::Cycle { pages },
+            })?;
+        if let Some((first, second)) = ordered
+            .0
             .iter()
-            .filter(|rule| pages.contains(&rule.before) && pages.contains(&rule.after))
-            .map(|rule| *rule)
-            .collect()
+            .tuple_windows()
+            .find(|(first, second)| !self.pairs.contains(&(**first, **second)))
+        {
+            return Err(RuleSetError::Incomparable {
+                first: *first,
+                second: *second,
+            });
+        }
+        Ok(PageOrder {
+            ranks: ordered
+                .0
+                .into_iter()
+                .enumerate()
+                .map(|(rank, page)| (page, rank))
+                .collect(),
+        })
+    }
+
+    /// Sorts an update's pages with the comparator from [`RuleSet::order`].
+    pub fn sort_update(&self, update: &Update) -> Result<Update, RuleSetError> {
+        let order = self.order(&update.0)?;
+        let mut pages = update.0.clone();
+        pages.sort_by(|first, second| {
+            order
+                .compare(*first, *second)
+                .expect("Every page of the update should be ordered")
+        });
+        Ok(Update(pages))
+    }
+}
+
+/// The order the rules put some pages in, for comparing any two of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageOrder {
+    ranks: HashMap<u32, usize>,
+}
+
+impl PageOrder {
+    /// How two pages are ordered, or `None` if either isn't one of the
+    /// ordered pages.
+    pub fn compare(&self, first: u32, second: u32) -> Option<Ordering> {
+        Some(self.ranks.get(&first)?.cmp(self.ranks.get(&second)?))
+    }
+}
+
+/// Why the rules can't be used to sort some pages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleSetError {
+    /// There are rules putting each page before the other.
+    Contradiction { first: u32, second: u32 },
+    /// The rules don't relate the two pages, even through other pages.
+    Incomparable { first: u32, second: u32 },
+    /// The rules go round in a loop: each page must come before the next,
+    /// and the last before the first.
+    Cycle { pages: Vec<u32> },
+}
+
+impl Display for RuleSetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Contradiction { first, second } => write!(
+                f,
+                "rules put page {first} both before and after page {second}"
+            ),
+            Self::Incomparable { first, second } => {
+                write!(f, "no rule orders pages {first} and {second}")
+            }
+            Self::Cycle { pages } => write!(
+                f,
+                "rules put pages in a loop: {}",
+                pages
+                    .iter()
+                    .chain(pages.first())
+                    .map(|page| page.to_string())
+                    .join(" before ")
+            ),
+        }
     }
 }
 
+impl Error for RuleSetError {}
+
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
-struct Rule {
-    before: u32,
-    after: u32,
+pub struct Rule {
+    pub before: u32,
+    pub after: u32,
 }
 
 impl From<Rule> for [u32; 2] {
@@ -38,57 +160,189 @@ impl From<Rule> for [u32; 2] {
     }
 }
 
+/// The rules relating the pages of an update contradict each other, so it
+/// can't be put in order. Holds the pages of one cycle, each of which must
+/// come before the next, and the last before the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+    pub pages: Vec<u32>,
+}
+
+impl Display for CycleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the rules for pages {} form a cycle",
+            self.pages
+                .iter()
+                .chain(self.pages.first())
+                .map(|page| page.to_string())
+                .join(" -> ")
+        )
+    }
+}
+
+impl Error for CycleError {}
+
 impl Update {
-    fn obeys(&self, rule: &Rule) -> bool {
-        let mut copy = self.0.clone();
-        copy.retain(|el| *el == rule.before || *el == rule.after);
-        return copy.len() < 2 || copy[0] == rule.before;
+    pub fn pages(&self) -> &[u32] {
+        &self.0
     }
 
-    fn satisfies(&self, ruleset: &RuleSet) -> bool {
-        !ruleset.rules.iter().any(|rule| !self.obeys(rule))
+    fn positions(&self) -> HashMap<u32, usize> {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(index, page)| (*page, index))
+            .collect()
     }
 
-    fn with_rules(&self, ruleset: &RuleSet) -> Self {
-        if self.satisfies(ruleset) {
-            return self.clone();
-        }
+    /// Every rule the update breaks, in the order of the pages which should
+    /// have come first.
+    pub fn violations(&self, ruleset: &RuleSet) -> Vec<Violation> {
+        let positions = self.positions();
+        self.0
+            .iter()
+            .enumerate()
+            .flat_map(|(before_index, page)| {
+                let positions = &positions;
+                ruleset.successors(*page).iter().filter_map(move |after| {
+                    let after_index = *positions.get(after)?;
+                    (after_index < before_index).then_some(Violation {
+                        rule: (*page, *after).into(),
+                        before_index,
+                        after_index,
+                    })
+                })
+            })
+            .collect()
+    }
 
-        let rules = ruleset.rules_involving(&self.0);
+    pub fn satisfies(&self, ruleset: &RuleSet) -> bool {
+        let positions = self.positions();
+        self.0.iter().enumerate().all(|(index, page)| {
+            ruleset.successors(*page).iter().all(|after| {
+                positions
+                    .get(after)
+                    .is_none_or(|position| *position > index)
+            })
+        })
+    }
 
-        let mut remaining_rules = rules.clone();
-        let mut ordered = vec![];
+    /// Reorders the pages to satisfy the rules, keeping pages which the rules
+    /// don't relate in their original order where possible. A page which
+    /// appears more than once is only kept the first time.
+    pub fn with_rules(&self, ruleset: &RuleSet) -> Result<Self, CycleError> {
+        // Kahn's algorithm, over the rules between pages of this update
+        let pages: Vec<u32> = self.0.iter().copied().unique().collect();
+        let positions = self.positions();
+        let edges_within = |page: u32| {
+            ruleset
+                .successors(page)
+                .iter()
+                .filter(|after| positions.contains_key(after))
+                .copied()
+        };
+        let mut in_degrees: HashMap<u32, usize> = pages.iter().map(|page| (*page, 0)).collect();
+        for page in &pages {
+            for after in edges_within(*page) {
+                *in_degrees.get_mut(&after).unwrap() += 1;
+            }
+        }
 
-        let tail = rules
-            .clone()
+        let mut ready: VecDeque<u32> = pages
             .iter()
-            .map(|rule| rule.after)
-            .find(|page| !rules.iter().map(|rule| rule.before).contains(&page))
-            .expect("No last page found")
-            .to_owned();
-
-        while remaining_rules.len() > 0 {
-            let head = remaining_rules
-                .clone()
+            .filter(|page| in_degrees[page] == 0)
+            .copied()
+            .collect();
+        let mut ordered = Vec::with_capacity(pages.len());
+        while let Some(page) = ready.pop_front() {
+            ordered.push(page);
+            for after in edges_within(page) {
+                let in_degree = in_degrees.get_mut(&after).unwrap();
+                *in_degree -= 1;
+                if *in_degree == 0 {
+                    ready.push_back(after);
+                }
+            }
+        }
+
+        if ordered.len() < pages.len() {
+            return Err(self.find_cycle(ruleset, &in_degrees));
+        }
+        Ok(Self(ordered))
+    }
+
+    /// Finds a cycle among the pages Kahn's algorithm couldn't order. Each
+    /// of them must have an unordered page before it, so walking backwards
+    /// from any of them has to come round in a loop.
+    fn find_cycle(&self, ruleset: &RuleSet, in_degrees: &HashMap<u32, usize>) -> CycleError {
+        let unordered = |page: &u32| in_degrees.get(page).is_some_and(|degree| *degree > 0);
+        let mut predecessors: HashMap<u32, u32> = HashMap::new();
+        for page in self.0.iter().filter(|page| unordered(page)) {
+            for after in ruleset
+                .successors(*page)
                 .iter()
-                .map(|rule| rule.before)
-                .find(|page| {
-                    !remaining_rules
-                        .iter()
-                        .map(|rule| rule.after)
-                        .contains(&page)
-                })
-                .expect("No first page found")
-                .to_owned();
-            ordered.push(head);
-            remaining_rules.retain(|rule| rule.before != head);
+                .filter(|after| unordered(after))
+            {
+                predecessors.entry(*after).or_insert(*page);
+            }
         }
-        ordered.push(tail);
 
-        Self(ordered)
+        let mut walked = vec![*self.0.iter().find(|page| unordered(page)).unwrap()];
+        loop {
+            let previous = predecessors[walked.last().unwrap()];
+            if let Some(start) = walked.iter().position(|page| *page == previous) {
+                let mut pages = walked.split_off(start);
+                pages.reverse();
+                return CycleError { pages };
+            }
+            walked.push(previous);
+        }
+    }
+}
+
+/// A rule broken by an update: the page which should come first is at
+/// `before_index`, after the other page at `after_index`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Violation {
+    pub rule: Rule,
+    pub before_index: usize,
+    pub after_index: usize,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{} (page {} at index {}, page {} at index {})",
+            self.rule.before,
+            self.rule.after,
+            self.rule.before,
+            self.before_index,
+            self.rule.after,
+            self.after_index
+        )
     }
 }
 
+/// Describes which rules each update breaks, if any, to show why it was
+/// judged to be in the wrong order.
+pub fn explain(input_lines: &str) -> String {
+    let parsed = Day05::parse_input(input_lines);
+    let mut explanation = String::new();
+    for update in &parsed.updates {
+        let pages = update.0.iter().join(",");
+        let violations = update.violations(&parsed.ruleset);
+        if violations.is_empty() {
+            explanation += &format!("{pages}: correct\n");
+        } else {
+            explanation += &format!("{pages}: breaks {}\n", violations.iter().join(", "));
+        }
+    }
+    explanation
+}
+
 impl From<(u32, u32)> for Rule {
     fn from(value: (u32, u32)) -> Self {
         Self {
@@ -104,8 +358,8 @@ impl From<(&str, &str)> for Rule {
     }
 }
 
-#[derive(Debug, Default, Clone)]
-struct Update(Vec<u32>);
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Update(pub Vec<u32>);
 
 impl Solution for Day05 {
     type ParsedInput = Parsed;
@@ -121,7 +375,7 @@ impl Solution for Day05 {
             rules.push((pages[0], pages[1]).into());
         }
         Parsed {
-            ruleset: RuleSet { rules },
+            ruleset: RuleSet::new(rules),
             updates: lines
                 .map(|update| {
                     Update(
@@ -154,7 +408,12 @@ impl Solution for Day05 {
             .updates
             .iter()
             .filter(|update| !update.satisfies(&parsed_input.ruleset))
-            .map(|update| update.with_rules(&parsed_input.ruleset))
+            .map(|update| {
+                parsed_input
+                    .ruleset
+                    .sort_update(update)
+                    .expect("Couldn't sort update")
+            })
             .filter_map(|update| {
                 // Assume odd length
                 let half = update.0.len() / 2;
@@ -243,6 +502,128 @@ mod tests {
         )
     }
 
+    #[test]
+    fn check_cycle_error() {
+        let ruleset = RuleSet::new(vec![
+            (1, 2).into(),
+            (2, 3).into(),
+            (3, 4).into(),
+            (4, 2).into(),
+            (4, 5).into(),
+        ]);
+        assert_eq!(
+            Update(vec![3, 5, 1, 2]).with_rules(&ruleset),
+            Ok(Update(vec![5, 1, 2, 3]))
+        );
+        assert!(!Update(vec![3, 5, 1, 2]).satisfies(&ruleset));
+        assert!(Update(vec![5, 1, 2, 3]).satisfies(&ruleset));
+
+        let error = Update(vec![5, 4, 3, 2, 1])
+            .with_rules(&ruleset)
+            .unwrap_err();
+        assert_eq!(error.pages.len(), 3);
+        for (before, after) in error.pages.iter().circular_tuple_windows() {
+            assert!(ruleset.rules().contains(&(*before, *after).into()));
+        }
+        assert!(error.to_string().ends_with("form a cycle"));
+
+        let ruleset = RuleSet::new(vec![(1, 2).into()]);
+        assert_eq!(
+            Update(vec![2, 1, 2]).with_rules(&ruleset),
+            Ok(Update(vec![1, 2]))
+        );
+    }
+
+    #[test]
+    fn check_comparator() {
+        let ruleset = RuleSet::new(vec![(1, 2).into(), (2, 3).into(), (1, 3).into()]);
+        let order = ruleset.order(&[3, 1, 2]).unwrap();
+        assert_eq!(order.compare(1, 3), Some(Ordering::Less));
+        assert_eq!(order.compare(3, 2), Some(Ordering::Greater));
+        assert_eq!(order.compare(2, 2), Some(Ordering::Equal));
+        assert_eq!(order.compare(1, 4), None);
+        assert_eq!(
+            ruleset.sort_update(&Update(vec![3, 1, 2])),
+            Ok(Update(vec![1, 2, 3]))
+        );
+        assert_eq!(
+            ruleset.sort_update(&Update(vec![3, 4])),
+            Err(RuleSetError::Incomparable {
+                first: 3,
+                second: 4
+            })
+        );
+
+        // 1 and 3 are only ordered through 2
+        let ruleset = RuleSet::new(vec![(1, 2).into(), (2, 3).into()]);
+        assert_eq!(
+            ruleset.order(&[3, 1, 2]).unwrap().compare(3, 1),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            ruleset.sort_update(&Update(vec![3, 1, 2])),
+            Ok(Update(vec![1, 2, 3]))
+        );
+        assert_eq!(Day05::solve_part_two("1|2\n2|3\n\n3,1,2"), "2".to_string());
+        assert_eq!(
+            ruleset.order(&[3, 1]),
+            Err(RuleSetError::Incomparable {
+                first: 3,
+                second: 1
+            })
+        );
+        assert_eq!(
+            ruleset.sort_update(&Update(vec![2, 1, 2])),
+            Ok(Update(vec![1, 2, 2]))
+        );
+
+        let ruleset = RuleSet::new(vec![(1, 2).into(), (2, 3).into(), (3, 1).into()]);
+        assert_eq!(
+            ruleset.sort_update(&Update(vec![2, 1, 3])),
+            Err(RuleSetError::Cycle {
+                pages: vec![3, 1, 2]
+            })
+        );
+        assert_eq!(
+            RuleSetError::Cycle {
+                pages: vec![3, 1, 2]
+            }
+            .to_string(),
+            "rules put pages in a loop: 3 before 1 before 2 before 3"
+        );
+        let ruleset = RuleSet::new(vec![(1, 2).into(), (2, 1).into()]);
+        assert!(matches!(
+            ruleset.order(&[1, 2]),
+            Err(RuleSetError::Contradiction { .. })
+        ));
+    }
+
+    #[test]
+    fn check_violations() {
+        let ruleset = RuleSet::new(vec![(97, 75).into(), (75, 13).into(), (47, 13).into()]);
+        let update = Update(vec![75, 97, 47, 13]);
+        assert_eq!(
+            update.violations(&ruleset),
+            vec![Violation {
+                rule: (97, 75).into(),
+                before_index: 1,
+                after_index: 0
+            }]
+        );
+        assert_eq!(
+            update.violations(&ruleset)[0].to_string(),
+            "97|75 (page 97 at index 1, page 75 at index 0)"
+        );
+        assert_eq!(Update(vec![13, 47, 75]).violations(&ruleset).len(), 2);
+        assert!(Update(vec![97, 75, 13]).violations(&ruleset).is_empty());
+
+        assert_eq!(
+            explain("97|75\n75|13\n\n97,75,13\n13,97,75"),
+            "97,75,13: correct\n\
+             13,97,75: breaks 75|13 (page 75 at index 2, page 13 at index 0)\n"
+        );
+    }
+
     #[test]
     fn check_day05_both_case1() {
         assert_eq!(Day05::solve("", false), ("0".to_string(), "0".to_string()))
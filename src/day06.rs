@@ -1,258 +1,257 @@
-use std::{
-    collections::HashSet,
-    fmt::{Display, Write},
-    ops::Index,
-    slice::{Iter, SliceIndex},
-    str::FromStr,
-};
+use std::collections::HashSet;
 
-use crate::Solution;
+use crate::{
+    grid::{Colour, Direction, Grid, GridCell, Image},
+    simulation::Simulation,
+    Solution,
+};
 
 #[derive(Clone, Debug)]
 pub struct Day06;
 
-#[derive(Clone)]
-pub struct Grid<T>(Vec<Vec<T>>);
-
-impl<T: Display> Display for Grid<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in &self.0 {
-            for element in row {
-                f.write_str(&element.to_string())?;
-            }
-            f.write_str("\n")?
-        }
-        Ok(())
-    }
+#[derive(Debug, PartialEq, Eq, Copy, Clone, GridCell)]
+pub enum MapElement {
+    Guard(Direction),
+    #[cell('#')]
+    Obstacle,
+    #[cell('.')]
+    Empty,
 }
 
-impl<T: FromStr> FromStr for Grid<T> {
-    type Err = T::Err;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(
-            s.lines()
-                .map(|row| {
-                    row.chars()
-                        .map(|character| character.to_string().parse())
-                        .collect::<Result<_, _>>()
-                })
-                .collect::<Result<_, _>>()?,
-        ))
-    }
+/// The guard's patrol as a step-by-step [`Simulation`], moving the guard
+/// around the grid until they leave it.
+pub struct Patrol {
+    grid: Grid<MapElement>,
+    guard: Option<(usize, usize)>,
+    direction: Direction,
+    walked_positions: HashSet<(usize, usize)>,
 }
 
-impl<T> Grid<T> {
-    pub fn get(&self, row_index: usize, col_index: usize) -> Option<&T> {
-        self.0.get(row_index).and_then(|row| row.get(col_index))
-    }
-
-    pub fn get_pos(&self, pos: (usize, usize)) -> Option<&T> {
-        self.get(pos.0, pos.1)
-    }
+impl Patrol {
+    pub fn new(grid: Grid<MapElement>) -> Self {
+        let (row, col, guard) = grid
+            .locate(|element| matches!(element, MapElement::Guard(..)))
+            .expect("Could not find the guard");
 
-    pub fn put(&mut self, row_index: usize, col_index: usize, element: T) -> bool {
-        self.0
-            .get_mut(row_index)
-            .and_then(|row| {
-                row.get_mut(col_index).and_then(|old| {
-                    *old = element;
-                    Some(())
-                })
-            })
-            .is_some()
-    }
+        let direction = *match guard {
+            MapElement::Guard(direction) => direction,
+            _ => unreachable!("Guard is not a guard"),
+        };
 
-    pub fn iter(&self) -> Iter<'_, Vec<T>> {
-        self.0.iter()
+        Self {
+            grid,
+            guard: Some((row, col)),
+            direction,
+            walked_positions: HashSet::from([(row, col)]),
+        }
     }
 
-    pub fn dimensions(&self) -> (usize, usize) {
-        // Assume rectangular, but cope with empty data structure
-        (self.0.len(), self.0.get(0).map_or(0, Vec::len))
+    pub fn walked_positions(&self) -> &HashSet<(usize, usize)> {
+        &self.walked_positions
     }
+}
 
-    /// Convenience function for row by index.
-    pub fn row(&self, row_index: usize) -> Option<Vec<&T>> {
-        self.0.get(row_index).map(|row| row.iter().collect())
-    }
+impl Simulation for Patrol {
+    type Cell = MapElement;
 
-    /// Constructs the column of this grid specified by the column index
-    /// and returns it.
-    pub fn column(&self, col_index: usize) -> Option<Vec<&T>> {
-        self.iter().map(|row| row.get(col_index)).collect()
+    fn step(&mut self) -> bool {
+        let Some((i, j)) = self.guard else {
+            return false;
+        };
+        match self.grid.walk(i, j, &self.direction) {
+            None => {
+                self.grid[(i, j)] = MapElement::Empty;
+                self.guard = None;
+            }
+            Some((_, _, MapElement::Obstacle)) => {
+                self.direction = self.direction.turn_right();
+                self.grid[(i, j)] = MapElement::Guard(self.direction);
+            }
+            Some((next_i, next_j, _)) => {
+                self.grid[(i, j)] = MapElement::Empty;
+                self.grid[(next_i, next_j)] = MapElement::Guard(self.direction);
+                self.guard = Some((next_i, next_j));
+                self.walked_positions.insert((next_i, next_j));
+            }
+        }
+        true
     }
 
-    /// Searches for the first (row-major ordering) element contained in
-    /// the grid that satisfies a predicate, and return it with its location
-    /// if found.
-    ///
-    /// Compare [`Iterator::find`], which does the same thing over a normal
-    /// [Iterator] (without returning index) - this wraps that operation
-    /// with [`Iterator::enumerate`] over two dimensions to return the position.
-    /// Compare also [`Iterator::position`], but this does not return the element
-    /// itself (though it could be subsequently obtained).
-    pub fn locate<P>(&self, mut predicate: P) -> Option<(usize, usize, &T)>
-    where
-        P: FnMut(&T) -> bool,
-    {
-        self.iter()
-            .enumerate()
-            .filter_map(|(row_index, row)| {
-                row.iter()
-                    .enumerate()
-                    .find(|(_, element)| predicate(element))
-                    .map(|(col_index, element)| (row_index, col_index, element))
-            })
-            .next()
+    fn grid(&self) -> &Grid<MapElement> {
+        &self.grid
     }
 
-    /// Return all elements (with their positions) contained in the grid that satisfiy a
-    /// predicate.
-    ///
-    /// Compare [`Iterator::filter`], which does the same thing over a normal
-    /// [Iterator] (without returning index) - this wraps that operation
-    /// with [`Iterator::enumerate`] over two dimensions to return the position.
-    pub fn locate_all<P>(&self, mut predicate: P) -> Vec<(usize, usize, &T)>
-    where
-        P: FnMut(&T) -> bool,
-    {
-        self.iter()
-            .enumerate()
-            .flat_map(|(row_index, row)| {
-                row.iter()
-                    .enumerate()
-                    .filter(|(_, element)| predicate(element))
-                    .map(|(col_index, element)| (row_index, col_index, element))
-                    .collect::<Vec<_>>()
-            })
-            .collect()
+    fn render(&self) -> String {
+        self.grid
+            .render()
+            .highlight(self.walked_positions.iter().copied(), Colour::Yellow)
+            .highlight(self.guard, Colour::Red)
+            .to_string()
     }
-}
 
-impl<T> Grid<T> {
-    pub fn walk(
-        &self,
-        row_index: usize,
-        col_index: usize,
-        direction: &Direction,
-    ) -> Option<(usize, usize, &T)> {
-        match direction {
-            Direction::Up => match row_index.overflowing_sub(1) {
-                (_, true) => None,
-                (result, false) => self
-                    .get(result, col_index)
-                    .map(|el| (result, col_index, el)),
+    fn draw(&self) -> Option<Image> {
+        let mut image = self.grid.to_image(
+            |element| match element {
+                MapElement::Obstacle => [64, 64, 64],
+                MapElement::Empty => [255, 255, 255],
+                MapElement::Guard(_) => [255, 0, 0],
             },
-            Direction::Down => self
-                .get(row_index + 1, col_index)
-                .map(|el| (row_index + 1, col_index, el)),
-            Direction::Left => match col_index.overflowing_sub(1) {
-                (_, true) => None,
-                (result, false) => self
-                    .get(row_index, result)
-                    .map(|el| (row_index, result, el)),
-            },
-            Direction::Right => self
-                .get(row_index, col_index + 1)
-                .map(|el| (row_index, col_index + 1, el)),
-        }
+            4,
+        );
+        image.paint(
+            self.walked_positions
+                .iter()
+                .copied()
+                .filter(|pos| Some(*pos) != self.guard),
+            [255, 200, 0],
+        );
+        Some(image)
     }
 }
 
-impl<T, Idx: SliceIndex<[Vec<T>], Output = Vec<T>>> Index<Idx> for Grid<T> {
-    type Output = Vec<T>;
+/// For every cell and direction, where the guard would next stop and turn:
+/// the cell just before the first obstacle ahead, or `None` if they would
+/// walk off the grid instead.
+struct JumpTable {
+    cols: usize,
+    stops: Vec<[Option<(usize, usize)>; 4]>,
+}
 
-    fn index(&self, index: Idx) -> &Self::Output {
-        self.0.index(index)
+/// How many steps it takes to walk from one position to another in a straight
+/// line, if the second is ahead of the first.
+fn steps_ahead(from: (usize, usize), to: (usize, usize), direction: Direction) -> Option<usize> {
+    match direction {
+        Direction::Up => (to.1 == from.1 && to.0 <= from.0).then(|| from.0 - to.0),
+        Direction::Down => (to.1 == from.1 && to.0 >= from.0).then(|| to.0 - from.0),
+        Direction::Left => (to.0 == from.0 && to.1 <= from.1).then(|| from.1 - to.1),
+        Direction::Right => (to.0 == from.0 && to.1 >= from.1).then(|| to.1 - from.1),
     }
 }
 
-impl<T> IntoIterator for Grid<T> {
-    type Item = Vec<T>;
-    type IntoIter = <Vec<Vec<T>> as IntoIterator>::IntoIter;
+impl JumpTable {
+    fn new(grid: &Grid<MapElement>) -> Self {
+        let (rows, cols) = grid.dimensions();
+        let mut table = Self {
+            cols,
+            stops: vec![[None; 4]; rows * cols],
+        };
+        for direction in Direction::ALL {
+            let (row_delta, col_delta) = direction.delta();
+            // Visit the cells so that the one in front of each is filled in first
+            let row_order: Vec<usize> = if row_delta > 0 {
+                (0..rows).rev().collect()
+            } else {
+                (0..rows).collect()
+            };
+            let col_order: Vec<usize> = if col_delta > 0 {
+                (0..cols).rev().collect()
+            } else {
+                (0..cols).collect()
+            };
+            for &i in &row_order {
+                for &j in &col_order {
+                    let stop = match grid.walk(i, j, &direction) {
+                        None => None,
+                        Some((_, _, MapElement::Obstacle)) => Some((i, j)),
+                        Some((next_i, next_j, _)) => {
+                            table.stops[table.index((next_i, next_j))][direction as usize]
+                        }
+                    };
+                    let index = table.index((i, j));
+                    table.stops[index][direction as usize] = stop;
+                }
+            }
+        }
+        table
+    }
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+    fn index(&self, pos: (usize, usize)) -> usize {
+        pos.0 * self.cols + pos.1
     }
-}
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-pub enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+    fn cell_count(&self) -> usize {
+        self.stops.len()
+    }
 
-impl Direction {
-    pub fn turn_right(&self) -> Self {
-        match self {
-            Self::Up => Self::Right,
-            Self::Right => Self::Down,
-            Self::Down => Self::Left,
-            Self::Left => Self::Up,
+    /// Where the guard stops from `pos`, taking into account one extra
+    /// obstacle which isn't in the table.
+    fn stop(
+        &self,
+        pos: (usize, usize),
+        direction: Direction,
+        obstacle: (usize, usize),
+    ) -> Option<(usize, usize)> {
+        let stop = self.stops[self.index(pos)][direction as usize];
+        match steps_ahead(pos, obstacle, direction) {
+            Some(steps)
+                if steps > 0
+                    && stop.is_none_or(|stop| {
+                        steps_ahead(pos, stop, direction).is_some_and(|limit| steps <= limit)
+                    }) =>
+            {
+                let (row_delta, col_delta) = direction.delta();
+                Some((
+                    obstacle.0.wrapping_add_signed(-row_delta),
+                    obstacle.1.wrapping_add_signed(-col_delta),
+                ))
+            }
+            _ => stop,
         }
     }
+
+    /// Whether the guard, setting off from `pos` with an extra obstacle
+    /// placed, ends up walking in a loop. Only the turns are followed, since
+    /// a loop must revisit a turn facing the same way.
+    fn is_looped(
+        &self,
+        mut pos: (usize, usize),
+        mut direction: Direction,
+        obstacle: (usize, usize),
+        visited: &mut VisitedStates,
+    ) -> bool {
+        visited.clear();
+        while let Some(stop) = self.stop(pos, direction, obstacle) {
+            pos = stop;
+            direction = direction.turn_right();
+            if !visited.insert(self.index(pos) * 4 + direction as usize) {
+                return true;
+            }
+        }
+        false
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
-pub enum MapElement {
-    Guard(Direction),
-    Obstacle,
-    Empty,
+/// A set of (cell, direction) states as a dense bitset, which remembers the
+/// words it touched so it can be cleared without wiping the whole thing.
+struct VisitedStates {
+    bits: Vec<u64>,
+    touched: Vec<usize>,
 }
 
-impl Display for MapElement {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
-            Self::Guard(Direction::Up) => f.write_char('^'),
-            Self::Guard(Direction::Down) => f.write_char('v'),
-            Self::Guard(Direction::Left) => f.write_char('<'),
-            Self::Guard(Direction::Right) => f.write_char('>'),
-            Self::Obstacle => f.write_char('#'),
-            Self::Empty => f.write_char('.'),
+impl VisitedStates {
+    fn new((rows, cols): (usize, usize)) -> Self {
+        Self {
+            bits: vec![0; (rows * cols * 4).div_ceil(64)],
+            touched: vec![],
         }
     }
-}
-#[derive(Debug, PartialEq, Eq)]
-pub struct ParseMapElementError;
-
-impl FromStr for MapElement {
-    type Err = ParseMapElementError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "^" => Ok(Self::Guard(Direction::Up)),
-            "v" => Ok(Self::Guard(Direction::Down)),
-            "<" => Ok(Self::Guard(Direction::Left)),
-            ">" => Ok(Self::Guard(Direction::Right)),
-            "#" => Ok(Self::Obstacle),
-            "." => Ok(Self::Empty),
-            _ => Err(ParseMapElementError),
+
+    /// Adds a state, returning whether it was newly added.
+    fn insert(&mut self, state: usize) -> bool {
+        let (word, bit) = (state / 64, 1 << (state % 64));
+        if self.bits[word] & bit != 0 {
+            return false;
         }
+        if self.bits[word] == 0 {
+            self.touched.push(word);
+        }
+        self.bits[word] |= bit;
+        true
     }
-}
 
-fn is_looped(
-    grid: &Grid<MapElement>,
-    row_index: usize,
-    col_index: usize,
-    mut direction: Direction,
-) -> bool {
-    let mut walked_positions: HashSet<(usize, usize, Direction)> = HashSet::new();
-    let mut i = row_index;
-    let mut j = col_index;
-
-    loop {
-        if walked_positions.contains(&(i, j, direction)) {
-            return true;
-        }
-        walked_positions.insert((i, j, direction));
-        match grid.walk(i, j, &direction) {
-            None => return false,
-            Some((_, _, MapElement::Obstacle)) => direction = direction.turn_right(),
-            Some((next_i, next_j, _)) => (i, j) = (next_i, next_j),
+    fn clear(&mut self) {
+        for word in self.touched.drain(..) {
+            self.bits[word] = 0;
         }
     }
 }
@@ -261,35 +260,18 @@ impl Solution for Day06 {
     type ParsedInput = Grid<MapElement>;
 
     fn parse_input(input_lines: &str) -> Self::ParsedInput {
-        input_lines.parse().expect("Couldn't parse input")
+        Grid::parse_with(input_lines, MapElement::try_from).expect("Couldn't parse input")
     }
 
     fn part_one(grid: &mut Self::ParsedInput) -> String {
-        let (row, col, guard) = grid
-            .locate(|element| matches!(element, MapElement::Guard(..)))
-            .expect("Could not find the guard");
-
-        let mut direction = *match guard {
-            MapElement::Guard(direction) => direction,
-            _ => unreachable!("Guard is not a guard"),
-        };
-        let mut walked_positions: HashSet<(usize, usize)> = HashSet::new();
-        let mut i = row;
-        let mut j = col;
+        let mut patrol = Patrol::new(grid.clone());
 
         // The following loop assumes we do terminate - i.e., no positions where the
         // guard is in some sense "surrounded" by obstacles, and thus will at some point
         // exit the grid.
-        loop {
-            walked_positions.insert((i, j));
-            match grid.walk(i, j, &direction) {
-                None => break,
-                Some((_, _, MapElement::Obstacle)) => direction = direction.turn_right(),
-                Some((next_i, next_j, _)) => (i, j) = (next_i, next_j),
-            }
-        }
+        while patrol.step() {}
 
-        walked_positions.len().to_string()
+        patrol.walked_positions().len().to_string()
     }
 
     fn part_two(grid: &mut Self::ParsedInput) -> String {
@@ -297,42 +279,40 @@ impl Solution for Day06 {
             .locate(|element| matches!(element, MapElement::Guard(..)))
             .expect("Could not find the guard");
 
-        let initial_direction = match guard {
-            MapElement::Guard(direction) => direction,
+        let mut direction = match guard {
+            MapElement::Guard(direction) => *direction,
             _ => unreachable!("Guard is not a guard"),
         };
 
-        let mut direction = *initial_direction;
-
-        let mut potential_obstacles: HashSet<(usize, usize)> = HashSet::new();
-        let mut i = row;
-        let mut j = col;
-
-        // The following loop assumes we do terminate - i.e., no positions where the
-        // guard is in some sense "surrounded" by obstacles, and thus will at some point
-        // exit the grid.
+        let jumps = JumpTable::new(grid);
+        let mut visited = VisitedStates::new(grid.dimensions());
+        let mut tried = vec![false; jumps.cell_count()];
+        // No obstacle can go where the guard is standing at the start
+        tried[jumps.index((row, col))] = true;
+        let mut potential_obstacles = 0;
+        let (mut i, mut j) = (row, col);
+
+        // An obstacle only changes the path from the first time the guard would
+        // run into it, so each candidate is checked once, resuming from the step
+        // just before it rather than from the start.
         loop {
             match grid.walk(i, j, &direction) {
                 None => break,
                 Some((_, _, MapElement::Obstacle)) => direction = direction.turn_right(),
                 Some((next_i, next_j, _)) => {
-                    let mut grid_with_obstacle = grid.clone();
-                    grid_with_obstacle.put(next_i, next_j, MapElement::Obstacle);
-                    // This sucks. The code naively checks the entire resulting grid
-                    // for a loop to decide if this is a potential obstacle spot,
-                    // which is super expensive. But it's Christmas Day and I can't be
-                    // bothered to think of a better way to do it right now.
-                    //
-                    // So there.
-                    if is_looped(&grid_with_obstacle, row, col, *initial_direction) {
-                        potential_obstacles.insert((next_i, next_j));
+                    let index = jumps.index((next_i, next_j));
+                    if !tried[index] {
+                        tried[index] = true;
+                        if jumps.is_looped((i, j), direction, (next_i, next_j), &mut visited) {
+                            potential_obstacles += 1;
+                        }
                     }
                     (i, j) = (next_i, next_j);
                 }
             }
         }
 
-        potential_obstacles.len().to_string()
+        potential_obstacles.to_string()
     }
 }
 
@@ -378,6 +358,16 @@ mod tests {
         )
     }
 
+    #[test]
+    fn check_day06_part2_case2() {
+        // The only loop comes from an obstacle on the guard's way back out,
+        // which sends them round past their own starting position
+        assert_eq!(
+            Day06::solve_part_two(".#..\n...#\n.^..\n..#."),
+            "1".to_string()
+        )
+    }
+
     #[test]
     fn check_day06_both_case1() {
         assert_eq!(Day06::solve("", false), ("0".to_string(), "0".to_string()))
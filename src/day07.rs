@@ -1,35 +1,512 @@
+use std::{
+    error::Error,
+    fmt::{Debug, Display},
+    ops::RangeInclusive,
+};
+
 use crate::Solution;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-pub enum Operator {
-    Plus,
-    Multiply,
-    Concatenate,
+/// A binary operator which can appear between the operands of an equation.
+///
+/// Operators are evaluated strictly left to right. The solver works
+/// backwards from an equation's goal, so as well as applying an operator it
+/// needs to be able to undo one.
+pub trait Operator: Debug + Sync {
+    /// How the operator is written, e.g. `+`.
+    fn symbol(&self) -> &'static str;
+
+    /// Applies the operator, or returns `None` if the result is undefined or
+    /// overflows.
+    fn apply(&self, op1: i64, op2: i64) -> Option<i64>;
+
+    /// Every left operand which gives `result` when combined with `op2`.
+    fn undo(&self, result: i64, op2: i64) -> LeftOperands;
+
+    /// Whether combining any left operand with `op2` gives `result`, e.g.
+    /// multiplying by zero. Those left operands are too many for
+    /// [`undo`](Operator::undo) to list, so the solver only needs whatever
+    /// comes before to evaluate at all.
+    fn absorbs(&self, _result: i64, _op2: i64) -> bool {
+        false
+    }
+
+    /// Whether the result is never negative when both operands aren't. When
+    /// that holds for every operator in use, the solver can abandon a branch
+    /// as soon as its goal goes negative.
+    fn preserves_non_negative(&self) -> bool {
+        true
+    }
+}
+
+/// The left operands which undo an operator, without listing them out: a
+/// division can be undone by a whole run of values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LeftOperands {
+    Empty,
+    One(i64),
+    Two(i64, i64),
+    Range(RangeInclusive<i64>),
 }
 
-impl Operator {
-    pub fn apply(&self, op1: i64, op2: i64) -> i64 {
+impl LeftOperands {
+    pub fn contains(&self, op1: i64) -> bool {
         match self {
-            Self::Plus => op1 + op2,
-            Self::Multiply => op1 * op2,
-            Self::Concatenate => (op1.to_string() + &op2.to_string()).parse().unwrap(),
+            LeftOperands::Empty => false,
+            LeftOperands::One(value) => *value == op1,
+            LeftOperands::Two(first, second) => *first == op1 || *second == op1,
+            LeftOperands::Range(range) => range.contains(&op1),
         }
     }
+
+    /// The left operands in ascending order.
+    pub fn values(self) -> impl Iterator<Item = i64> {
+        let (few, range) = match self {
+            LeftOperands::Empty => ([None, None], None),
+            LeftOperands::One(value) => ([Some(value), None], None),
+            LeftOperands::Two(first, second) => ([Some(first), Some(second)], None),
+            LeftOperands::Range(range) => ([None, None], Some(range)),
+        };
+        few.into_iter().flatten().chain(range.into_iter().flatten())
+    }
+}
+
+impl From<Option<i64>> for LeftOperands {
+    fn from(value: Option<i64>) -> Self {
+        value.map_or(LeftOperands::Empty, LeftOperands::One)
+    }
+}
+
+impl Display for dyn Operator + '_ {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl PartialEq for dyn Operator + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol() == other.symbol()
+    }
+}
+
+impl Eq for dyn Operator + '_ {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Plus;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Multiply;
+
+/// Joins the digits of the operands, e.g. `12 || 345 = 12345`. Only defined
+/// for non-negative operands.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Concatenate;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Subtract;
+
+/// Integer division, rounding towards zero.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Divide;
+
+/// Raises the left operand to the power of the right.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Exponent;
+
+/// Bitwise exclusive or.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Xor;
+
+/// Every operator, for selecting from with [`select_operators`].
+pub const ALL_OPERATORS: [&dyn Operator; 7] = [
+    &Plus,
+    &Multiply,
+    &Concatenate,
+    &Subtract,
+    &Divide,
+    &Exponent,
+    &Xor,
+];
+const PART_ONE_OPERATORS: [&dyn Operator; 2] = [&Multiply, &Plus];
+const PART_TWO_OPERATORS: [&dyn Operator; 3] = [&Multiply, &Plus, &Concatenate];
+
+/// A symbol in an operator list which doesn't belong to any operator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownOperatorError(pub String);
+
+impl Display for UnknownOperatorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown operator {:?}", self.0)
+    }
+}
+
+impl Error for UnknownOperatorError {}
+
+/// Looks up operators by their symbols, e.g. `["+", "*", "||"]`, in the order
+/// the solver should try them.
+pub fn select_operators<S: AsRef<str>>(
+    symbols: &[S],
+) -> Result<Vec<&'static dyn Operator>, UnknownOperatorError> {
+    symbols
+        .iter()
+        .map(|symbol| {
+            ALL_OPERATORS
+                .into_iter()
+                .find(|operator| operator.symbol() == symbol.as_ref())
+                .ok_or_else(|| UnknownOperatorError(symbol.as_ref().to_string()))
+        })
+        .collect()
+}
+
+/// The power of ten which shifts a number left far enough to concatenate
+/// `operand` onto its end.
+fn concatenation_shift(operand: i64) -> Option<i64> {
+    let mut shift: i64 = 10;
+    while shift <= operand {
+        shift = shift.checked_mul(10)?;
+    }
+    Some(shift)
 }
 
-fn words<T>(symbols: &Vec<T>, length: usize) -> Vec<Vec<&T>> {
-    let mut words = vec![vec![]];
-    for _ in 0..length {
-        words = words
-            .into_iter()
-            .flat_map(|word| {
-                symbols
-                    .iter()
-                    .map(move |symbol| [word.clone(), vec![symbol]].concat())
+/// The largest `root` with `root.pow(exponent) <= n`, for exponents of at
+/// least two.
+fn integer_root(n: u64, exponent: u32) -> u64 {
+    let (mut low, mut high) = (0, n.min(1 << 32));
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        if mid.checked_pow(exponent).is_some_and(|power| power <= n) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    low
+}
+
+impl Operator for Plus {
+    fn symbol(&self) -> &'static str {
+        "+"
+    }
+
+    fn apply(&self, op1: i64, op2: i64) -> Option<i64> {
+        op1.checked_add(op2)
+    }
+
+    fn undo(&self, result: i64, op2: i64) -> LeftOperands {
+        result.checked_sub(op2).into()
+    }
+}
+
+impl Operator for Multiply {
+    fn symbol(&self) -> &'static str {
+        "*"
+    }
+
+    fn apply(&self, op1: i64, op2: i64) -> Option<i64> {
+        op1.checked_mul(op2)
+    }
+
+    fn undo(&self, result: i64, op2: i64) -> LeftOperands {
+        // Anything times zero is zero, which is left to `absorbs`
+        if op2 != 0 && result % op2 == 0 {
+            LeftOperands::One(result / op2)
+        } else {
+            LeftOperands::Empty
+        }
+    }
+
+    fn absorbs(&self, result: i64, op2: i64) -> bool {
+        op2 == 0 && result == 0
+    }
+}
+
+impl Operator for Concatenate {
+    fn symbol(&self) -> &'static str {
+        "||"
+    }
+
+    fn apply(&self, op1: i64, op2: i64) -> Option<i64> {
+        if op1 < 0 || op2 < 0 {
+            return None;
+        }
+        op1.checked_mul(concatenation_shift(op2)?)?.checked_add(op2)
+    }
+
+    fn undo(&self, result: i64, op2: i64) -> LeftOperands {
+        match concatenation_shift(op2) {
+            Some(shift) if result >= 0 && op2 >= 0 && result % shift == op2 => {
+                LeftOperands::One(result / shift)
+            }
+            _ => LeftOperands::Empty,
+        }
+    }
+}
+
+impl Operator for Subtract {
+    fn symbol(&self) -> &'static str {
+        "-"
+    }
+
+    fn apply(&self, op1: i64, op2: i64) -> Option<i64> {
+        op1.checked_sub(op2)
+    }
+
+    fn undo(&self, result: i64, op2: i64) -> LeftOperands {
+        result.checked_add(op2).into()
+    }
+
+    fn preserves_non_negative(&self) -> bool {
+        false
+    }
+}
+
+impl Operator for Divide {
+    fn symbol(&self) -> &'static str {
+        "/"
+    }
+
+    fn apply(&self, op1: i64, op2: i64) -> Option<i64> {
+        op1.checked_div(op2)
+    }
+
+    fn undo(&self, result: i64, op2: i64) -> LeftOperands {
+        if op2 == 0 {
+            return LeftOperands::Empty;
+        }
+        // Dividing by a negative number only flips the sign of the result.
+        // Working in i128 means none of the bounds below can overflow.
+        let (result, op2) = if op2 < 0 {
+            (-i128::from(result), -i128::from(op2))
+        } else {
+            (i128::from(result), i128::from(op2))
+        };
+        // Rounding towards zero means every left operand in a run of `op2`
+        // values gives the same result
+        let exact = result * op2;
+        let (low, high) = match result.signum() {
+            1 => (exact, exact + (op2 - 1)),
+            0 => (1 - op2, op2 - 1),
+            _ => (exact - (op2 - 1), exact),
+        };
+        match (
+            i64::try_from(low.max(i64::MIN.into())),
+            i64::try_from(high.min(i64::MAX.into())),
+        ) {
+            (Ok(low), Ok(high)) if low <= high => LeftOperands::Range(low..=high),
+            _ => LeftOperands::Empty,
+        }
+    }
+}
+
+impl Operator for Exponent {
+    fn symbol(&self) -> &'static str {
+        "**"
+    }
+
+    fn apply(&self, op1: i64, op2: i64) -> Option<i64> {
+        op1.checked_pow(op2.try_into().ok()?)
+    }
+
+    fn undo(&self, result: i64, op2: i64) -> LeftOperands {
+        // Anything to the power of zero is one, which is left to `absorbs`
+        let Ok(exponent) = u32::try_from(op2) else {
+            return LeftOperands::Empty;
+        };
+        match exponent {
+            0 => return LeftOperands::Empty,
+            1 => return LeftOperands::One(result),
+            _ => {}
+        }
+        let root = integer_root(result.unsigned_abs(), exponent);
+        if root.checked_pow(exponent) != Some(result.unsigned_abs()) {
+            return LeftOperands::Empty;
+        }
+        // Roots of exponents above one are at most 2^32, so fit in an i64
+        let root = root as i64;
+        match (exponent % 2 == 0, result < 0) {
+            (true, true) => LeftOperands::Empty,
+            (true, false) if root == 0 => LeftOperands::One(0),
+            (true, false) => LeftOperands::Two(-root, root),
+            (false, true) => LeftOperands::One(-root),
+            (false, false) => LeftOperands::One(root),
+        }
+    }
+
+    fn absorbs(&self, result: i64, op2: i64) -> bool {
+        op2 == 0 && result == 1
+    }
+}
+
+impl Operator for Xor {
+    fn symbol(&self) -> &'static str {
+        "^"
+    }
+
+    fn apply(&self, op1: i64, op2: i64) -> Option<i64> {
+        Some(op1 ^ op2)
+    }
+
+    fn undo(&self, result: i64, op2: i64) -> LeftOperands {
+        LeftOperands::One(result ^ op2)
+    }
+}
+
+/// A choice of operators which makes an equation's operands evaluate to its
+/// goal, displayed as e.g. `3267 = 81 * 40 + 27`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Witness {
+    pub goal: i64,
+    pub operands: Vec<i64>,
+    pub operators: Vec<&'static dyn Operator>,
+}
+
+impl Display for Witness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} =", self.goal)?;
+        if let Some(first) = self.operands.first() {
+            write!(f, " {first}")?;
+        }
+        for (operator, operand) in self.operators.iter().zip(self.operands.iter().skip(1)) {
+            write!(f, " {operator} {operand}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Evaluates operands strictly left to right with the given operators, or
+/// returns `None` if any step is undefined or overflows.
+pub fn evaluate(operands: &[i64], operators: &[&dyn Operator]) -> Option<i64> {
+    let (first, rest) = operands.split_first()?;
+    rest.iter()
+        .zip(operators)
+        .try_fold(*first, |acc, (operand, operator)| {
+            operator.apply(acc, *operand)
+        })
+}
+
+/// Finds operators to go between the operands which make them evaluate to
+/// `goal`, if there are any. Operators are tried in the order given.
+///
+/// Works backwards from the goal, undoing the last operand with each operator
+/// in turn: a multiplication can only be undone when the goal divides evenly,
+/// and a concatenation when the goal ends in the operand's digits, so most
+/// branches die after a step or two. Branches whose goal goes negative are
+/// abandoned too, unless an operand or operator could make it so.
+pub fn find_operators(
+    goal: i64,
+    operands: &[i64],
+    operators: &[&'static dyn Operator],
+) -> Option<Vec<&'static dyn Operator>> {
+    let allow_negative = operands.iter().any(|operand| *operand < 0)
+        || operators
+            .iter()
+            .any(|operator| !operator.preserves_non_negative());
+    search(goal, operands, operators, allow_negative)
+}
+
+fn search(
+    goal: i64,
+    operands: &[i64],
+    operators: &[&'static dyn Operator],
+    allow_negative: bool,
+) -> Option<Vec<&'static dyn Operator>> {
+    if goal < 0 && !allow_negative {
+        return None;
+    }
+    match operands {
+        [] => None,
+        [first] => (*first == goal).then(Vec::new),
+        [rest @ .., last] => operators.iter().find_map(|operator| {
+            let mut found = if operator.absorbs(goal, *last) {
+                any_evaluation(rest, operators)
+            } else {
+                let previous = operator.undo(goal, *last);
+                match rest {
+                    // Saves walking through a long run of values just to find one
+                    [first] => previous.contains(*first).then(Vec::new),
+                    _ => previous
+                        .values()
+                        .find_map(|previous| search(previous, rest, operators, allow_negative)),
+                }
+            }?;
+            found.push(*operator);
+            Some(found)
+        }),
+    }
+}
+
+/// Finds operators which make the operands evaluate to anything at all,
+/// i.e. without any step being undefined or overflowing.
+fn any_evaluation(
+    operands: &[i64],
+    operators: &[&'static dyn Operator],
+) -> Option<Vec<&'static dyn Operator>> {
+    fn extend(
+        value: i64,
+        operands: &[i64],
+        operators: &[&'static dyn Operator],
+        found: &mut Vec<&'static dyn Operator>,
+    ) -> bool {
+        let Some((next, rest)) = operands.split_first() else {
+            return true;
+        };
+        operators.iter().any(|operator| {
+            let Some(value) = operator.apply(value, *next) else {
+                return false;
+            };
+            found.push(*operator);
+            extend(value, rest, operators, found) || {
+                found.pop();
+                false
+            }
+        })
+    }
+
+    let (first, rest) = operands.split_first()?;
+    let mut found = Vec::new();
+    extend(*first, rest, operators, &mut found).then_some(found)
+}
+
+/// Whether some choice of operators between the operands makes them evaluate
+/// to `goal`.
+pub fn is_solvable(goal: i64, operands: &[i64], operators: &[&'static dyn Operator]) -> bool {
+    find_operators(goal, operands, operators).is_some()
+}
+
+/// The witness for each equation, or `None` for those with no solution.
+pub fn witnesses(
+    equations: &[(i64, Vec<i64>)],
+    operators: &[&'static dyn Operator],
+) -> Vec<Option<Witness>> {
+    equations
+        .iter()
+        .map(|(goal, operands)| {
+            find_operators(*goal, operands, operators).map(|found| Witness {
+                goal: *goal,
+                operands: operands.clone(),
+                operators: found,
             })
-            .collect();
+        })
+        .collect()
+}
+
+/// Describes how each equation was solved, or that it couldn't be, for both
+/// parts of the puzzle.
+pub fn explain(input_lines: &str) -> String {
+    let equations = Day07::parse_input(input_lines);
+    let mut explanation = String::new();
+    for (part, operators) in [
+        ("one", &PART_ONE_OPERATORS[..]),
+        ("two", &PART_TWO_OPERATORS[..]),
+    ] {
+        explanation += &format!("Part {part}:\n");
+        for ((goal, _), witness) in equations.iter().zip(witnesses(&equations, operators)) {
+            match witness {
+                Some(witness) => explanation += &format!("{witness}\n"),
+                None => explanation += &format!("{goal}: no solution\n"),
+            }
+        }
     }
-    words
+    explanation
 }
 
 #[derive(Clone, Debug)]
@@ -59,21 +536,7 @@ impl Solution for Day07 {
     fn part_one(parsed_input: &mut Self::ParsedInput) -> String {
         parsed_input
             .iter()
-            .filter(|(goal, operands)| {
-                let symbols = vec![Operator::Multiply, Operator::Plus];
-                words(&symbols, operands.len() - 1)
-                    .iter()
-                    .any(|permutation| {
-                        let operands_iter = operands.iter().map(|el| *el);
-                        let mut operator_iter = permutation.into_iter();
-                        operands_iter
-                            .reduce(|acc, operand| {
-                                operator_iter.next().unwrap().apply(acc, operand)
-                            })
-                            .unwrap()
-                            == *goal
-                    })
-            })
+            .filter(|(goal, operands)| is_solvable(*goal, operands, &PART_ONE_OPERATORS))
             .map(|(goal, _)| goal)
             .sum::<i64>()
             .to_string()
@@ -82,21 +545,7 @@ impl Solution for Day07 {
     fn part_two(parsed_input: &mut Self::ParsedInput) -> String {
         parsed_input
             .iter()
-            .filter(|(goal, operands)| {
-                let symbols = vec![Operator::Multiply, Operator::Plus, Operator::Concatenate];
-                words(&symbols, operands.len() - 1)
-                    .iter()
-                    .any(|permutation| {
-                        let operands_iter = operands.iter().map(|el| *el);
-                        let mut operator_iter = permutation.into_iter();
-                        operands_iter
-                            .reduce(|acc, operand| {
-                                operator_iter.next().unwrap().apply(acc, operand)
-                            })
-                            .unwrap()
-                            == *goal
-                    })
-            })
+            .filter(|(goal, operands)| is_solvable(*goal, operands, &PART_TWO_OPERATORS))
             .map(|(goal, _)| goal)
             .sum::<i64>()
             .to_string()
@@ -143,6 +592,154 @@ mod tests {
         )
     }
 
+    #[test]
+    fn check_operators_apply_and_undo() {
+        assert_eq!(Concatenate.apply(12, 345), Some(12345));
+        assert_eq!(Concatenate.apply(15, 0), Some(150));
+        assert_eq!(Concatenate.apply(i64::MAX / 10, 99), None);
+        assert_eq!(Multiply.apply(i64::MAX, 2), None);
+
+        assert_eq!(Concatenate.undo(12345, 345), LeftOperands::One(12));
+        assert_eq!(Concatenate.undo(12345, 45), LeftOperands::One(123));
+        assert_eq!(Concatenate.undo(12345, 44), LeftOperands::Empty);
+        assert_eq!(Concatenate.undo(150, 0), LeftOperands::One(15));
+        assert_eq!(Multiply.undo(3267, 27), LeftOperands::One(121));
+        assert_eq!(Multiply.undo(3267, 40), LeftOperands::Empty);
+        assert_eq!(Plus.undo(10, 11), LeftOperands::One(-1));
+    }
+
+    #[test]
+    fn check_extra_operators() {
+        for operator in ALL_OPERATORS {
+            for op1 in -20..=20 {
+                for op2 in -5..=5 {
+                    if let Some(result) = operator.apply(op1, op2) {
+                        let undone = operator.undo(result, op2);
+                        assert!(
+                            undone
+                                .clone()
+                                .values()
+                                .all(|previous| operator.apply(previous, op2) == Some(result)),
+                            "{op1} {operator} {op2}"
+                        );
+                        assert!(
+                            operator.absorbs(result, op2) || undone.contains(op1),
+                            "{op1} {operator} {op2}"
+                        );
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            Divide.undo(3, 4).values().collect::<Vec<_>>(),
+            vec![12, 13, 14, 15]
+        );
+        assert_eq!(
+            Divide.undo(-3, -4).values().collect::<Vec<_>>(),
+            vec![12, 13, 14, 15]
+        );
+        assert!(Divide.undo(i64::MIN / 2, 2).contains(i64::MIN));
+        assert_eq!(Divide.undo(i64::MIN, -1), LeftOperands::Empty);
+        assert_eq!(
+            Divide.undo(0, i64::MIN),
+            LeftOperands::Range(i64::MIN + 1..=i64::MAX)
+        );
+        assert_eq!(Exponent.undo(81, 4), LeftOperands::Two(-3, 3));
+        assert_eq!(Exponent.undo(-27, 3), LeftOperands::One(-3));
+        assert_eq!(
+            Xor.undo(Xor.apply(12, 10).unwrap(), 10),
+            LeftOperands::One(12)
+        );
+        assert_eq!(Subtract.undo(-2, 5), LeftOperands::One(3));
+
+        // Roots too big to find exactly through floating point
+        assert_eq!(Exponent.undo(i64::MAX, 1), LeftOperands::One(i64::MAX));
+        assert_eq!(
+            Exponent.undo(3_037_000_499 * 3_037_000_499, 2),
+            LeftOperands::Two(-3_037_000_499, 3_037_000_499)
+        );
+        assert_eq!(
+            Exponent.undo(3_037_000_499 * 3_037_000_499 - 1, 2),
+            LeftOperands::Empty
+        );
+        assert_eq!(
+            Exponent.undo(-(2_097_151_i64.pow(3)), 3),
+            LeftOperands::One(-2_097_151)
+        );
+        assert_eq!(Exponent.undo(i64::MIN, 63), LeftOperands::One(-2));
+    }
+
+    #[test]
+    fn check_operator_selection() {
+        let operators = select_operators(&["-", "+"]).unwrap();
+        let found = find_operators(8, &[3, 5, 10], &operators).unwrap();
+        assert_eq!(evaluate(&[3, 5, 10], &found), Some(8));
+        assert!(!is_solvable(8, &[3, 5, 10], &PART_TWO_OPERATORS));
+
+        let operators = select_operators(&["**", "/", "^"]).unwrap();
+        assert!(is_solvable(40, &[3, 4, 2], &operators));
+        assert!(is_solvable(1, &[7, 0], &operators));
+        assert!(is_solvable(0, &[5, 1_000_000_000_000], &operators));
+        assert!(!is_solvable(1, &[7, 1_000_000_000_000], &operators));
+        let divide_first = select_operators(&["/", "+"]).unwrap();
+        assert_eq!(
+            find_operators(-4, &[4, -1], &divide_first),
+            Some(vec![&Divide as &dyn Operator])
+        );
+        assert!(is_solvable(0, &[0, -2], &divide_first[..1]));
+        assert_eq!(
+            select_operators(&["+", "%"]).map(|_| ()),
+            Err(UnknownOperatorError("%".to_string()))
+        );
+    }
+
+    #[test]
+    fn check_solver() {
+        let all = PART_TWO_OPERATORS;
+        assert!(is_solvable(7290, &[6, 8, 6, 15], &all));
+        assert!(!is_solvable(7290, &[6, 8, 6, 15], &PART_ONE_OPERATORS));
+        assert!(!is_solvable(21037, &[9, 7, 18, 13], &all));
+        assert!(!is_solvable(0, &[], &all));
+
+        // Multiplying by zero at the end leaves the rest free to be anything
+        let found = find_operators(0, &[5, 0], &PART_ONE_OPERATORS).unwrap();
+        assert_eq!(evaluate(&[5, 0], &found), Some(0));
+        let plus_first = select_operators(&["+", "*"]).unwrap();
+        let found = find_operators(0, &[i64::MAX, 1, 1, 0], &plus_first).unwrap();
+        assert_eq!(evaluate(&[i64::MAX, 1, 1, 0], &found), Some(0));
+        assert!(!is_solvable(0, &[i64::MAX, 2, 0], &PART_ONE_OPERATORS));
+        assert_eq!(
+            evaluate(&[6, 8, 6, 15], &[&Multiply, &Concatenate, &Multiply]),
+            Some(7290)
+        );
+    }
+
+    #[test]
+    fn check_witnesses() {
+        let witness = Witness {
+            goal: 3267,
+            operands: vec![81, 40, 27],
+            operators: vec![&Multiply, &Plus],
+        };
+        assert_eq!(witness.to_string(), "3267 = 81 * 40 + 27");
+        let empty = Witness {
+            goal: 0,
+            operands: vec![],
+            operators: vec![],
+        };
+        assert_eq!(empty.to_string(), "0 =");
+
+        let equations = Day07::parse_input("156: 15 6\n83: 17 5\n7290: 6 8 6 15");
+        let found = witnesses(&equations, &PART_TWO_OPERATORS);
+        assert_eq!(found[0].as_ref().unwrap().to_string(), "156 = 15 || 6");
+        assert_eq!(found[1], None);
+        let witness = found[2].as_ref().unwrap();
+        assert_eq!(
+            evaluate(&witness.operands, &witness.operators),
+            Some(witness.goal)
+        );
+    }
+
     #[test]
     fn check_day07_both_case1() {
         assert_eq!(Day07::solve("", false), ("0".to_string(), "0".to_string()))
@@ -2,22 +2,25 @@ use std::collections::HashSet;
 
 use itertools::Itertools;
 
-use crate::{day06::Grid, Solution};
+use crate::{
+    grid::{Grid, SparseGrid},
+    Solution,
+};
 
 #[derive(Clone, Debug)]
 pub struct Day08;
 
 impl Solution for Day08 {
-    type ParsedInput = (Grid<char>, HashSet<char>);
+    type ParsedInput = (SparseGrid<char>, HashSet<char>);
 
     fn parse_input(input_lines: &str) -> Self::ParsedInput {
         let grid: Grid<char> = input_lines.parse().unwrap();
+        // Antinodes can fall outside the map, so work in signed coordinates.
+        let grid = SparseGrid::from_grid(grid, '.');
         let unique_chars = grid
-            .iter()
-            .flatten()
-            .filter(|ch| **ch != '.')
-            .map(|el| *el)
-            .unique()
+            .iter_cells()
+            .map(|(_, ch)| *ch)
+            .filter(|ch| *ch != '.')
             .collect();
 
         (grid, unique_chars)
@@ -25,7 +28,6 @@ impl Solution for Day08 {
 
     fn part_one(parsed_input: &mut Self::ParsedInput) -> String {
         let (grid, unique_chars) = parsed_input;
-        let (rows, cols) = grid.dimensions();
         unique_chars
             .iter()
             .flat_map(|antenna_type| {
@@ -36,20 +38,13 @@ impl Solution for Day08 {
                     .flat_map(|combination| {
                         let a = combination[0];
                         let b = combination[1];
-                        let ab = (b.0 as isize - a.0 as isize, b.1 as isize - a.1 as isize);
-                        let a_node = (a.0 as isize - ab.0, a.1 as isize - ab.1);
-                        let b_node = (b.0 as isize + ab.0, b.1 as isize + ab.1);
-                        let mut valid_nodes = vec![];
-                        for node in [a_node, b_node] {
-                            if node.0 >= 0
-                                && node.0 < rows as isize
-                                && node.1 >= 0
-                                && node.1 < cols as isize
-                            {
-                                valid_nodes.push(node);
-                            }
-                        }
-                        valid_nodes
+                        let ab = (b.0 - a.0, b.1 - a.1);
+                        let a_node = (a.0 - ab.0, a.1 - ab.1);
+                        let b_node = (b.0 + ab.0, b.1 + ab.1);
+                        [a_node, b_node]
+                            .into_iter()
+                            .filter(|node| grid.get_pos(*node).is_some())
+                            .collect_vec()
                     })
                     .collect_vec()
             })
@@ -60,7 +55,6 @@ impl Solution for Day08 {
 
     fn part_two(parsed_input: &mut Self::ParsedInput) -> String {
         let (grid, unique_chars) = parsed_input;
-        let (rows, cols) = grid.dimensions();
         unique_chars
             .iter()
             .flat_map(|antenna_type| {
@@ -71,23 +65,18 @@ impl Solution for Day08 {
                     .flat_map(|combination| {
                         let a = combination[0];
                         let b = combination[1];
-                        let ab = (b.0 as isize - a.0 as isize, b.1 as isize - a.1 as isize);
+                        let ab = (b.0 - a.0, b.1 - a.1);
 
                         let mut valid_nodes = vec![];
-                        let is_valid = |node: (isize, isize)| {
-                            node.0 >= 0
-                                && node.0 < rows as isize
-                                && node.1 >= 0
-                                && node.1 < cols as isize
-                        };
-
-                        let mut antinode = (a.0 as isize, a.1 as isize);
+                        let is_valid = |node: (isize, isize)| grid.get_pos(node).is_some();
+
+                        let mut antinode = a;
                         while is_valid(antinode) {
                             valid_nodes.push(antinode);
                             antinode = (antinode.0 - ab.0, antinode.1 - ab.1);
                         }
 
-                        antinode = (b.0 as isize, b.1 as isize);
+                        antinode = b;
                         while is_valid(antinode) {
                             valid_nodes.push(antinode);
                             antinode = (antinode.0 + ab.0, antinode.1 + ab.1);
@@ -1,60 +1,318 @@
-use itertools::Itertools;
+use std::{
+    cmp::Reverse, collections::BinaryHeap, error::Error, fmt::Display, iter::repeat_n, str::FromStr,
+};
 
 use crate::Solution;
 
 #[derive(Clone, Debug)]
 pub struct Day09;
 
-impl Solution for Day09 {
-    type ParsedInput = Vec<i64>;
+/// A single block on the disk.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Block {
+    File(u64),
+    Free,
+}
 
-    fn parse_input(input_lines: &str) -> Self::ParsedInput {
-        let mut file = true;
-        let mut id = 0;
-        input_lines
+/// A run of consecutive blocks holding the same file, or free space.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Span {
+    File { id: u64, length: u64 },
+    Free { length: u64 },
+}
+
+impl Span {
+    pub fn length(&self) -> u64 {
+        match self {
+            Self::File { length, .. } | Self::Free { length } => *length,
+        }
+    }
+
+    fn is_file(&self) -> bool {
+        matches!(self, Self::File { .. })
+    }
+}
+
+/// The layout of files and free space on the disk, as a sequence of spans.
+///
+/// Parses from the puzzle's dense format, where digits alternate between file
+/// and free span lengths and file IDs count up from zero, and displays as one
+/// character per block, e.g. `00...111...2`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiskMap {
+    spans: Vec<Span>,
+}
+
+/// Why a [`DiskMap`] could not be parsed: a character which isn't a digit,
+/// counting from zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDiskMapError {
+    pub position: usize,
+    pub character: char,
+}
+
+impl Display for ParseDiskMapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid character {:?} at position {} of the disk map",
+            self.character, self.position
+        )
+    }
+}
+
+impl Error for ParseDiskMapError {}
+
+impl FromStr for DiskMap {
+    type Err = ParseDiskMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spans = s
+            .trim()
             .chars()
-            .flat_map(|ch| {
-                let digit = ch.to_digit(10).unwrap();
-                if file {
-                    file = false;
-                    vec![id; digit as usize]
+            .enumerate()
+            .map(|(position, character)| {
+                let length = character
+                    .to_digit(10)
+                    .ok_or(ParseDiskMapError {
+                        position,
+                        character,
+                    })?
+                    .into();
+                Ok(if position % 2 == 0 {
+                    Span::File {
+                        id: position as u64 / 2,
+                        length,
+                    }
                 } else {
-                    file = true;
-                    id += 1;
-                    vec![-1; digit as usize]
-                }
+                    Span::Free { length }
+                })
             })
-            .collect_vec()
+            .collect::<Result<_, _>>()?;
+        Ok(Self { spans })
     }
+}
 
-    fn part_one(parsed_input: &mut Self::ParsedInput) -> String {
-        let file_blocks = parsed_input.iter().filter(|id| **id != -1).count();
-        let is_compacted = |disk: Vec<i64>| {
-            disk.iter()
-                .position(|id| *id == -1)
-                .is_some_and(|idx| idx == file_blocks)
+impl Display for DiskMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for block in self.blocks() {
+            match block {
+                Block::File(id) => write!(f, "{id}")?,
+                Block::Free => f.write_str(".")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The checksum contribution of a file with the given ID occupying `length`
+/// blocks from `position`.
+fn checksum_of(id: u64, position: u64, length: u64) -> u64 {
+    id * (length * position + length * length.saturating_sub(1) / 2)
+}
+
+impl DiskMap {
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = Block> + '_ {
+        self.spans.iter().flat_map(|span| match span {
+            Span::File { id, length } => repeat_n(Block::File(*id), *length as usize),
+            Span::Free { length } => repeat_n(Block::Free, *length as usize),
+        })
+    }
+
+    /// The total number of blocks on the disk.
+    pub fn size(&self) -> u64 {
+        self.spans.iter().map(Span::length).sum()
+    }
+
+    /// The map in the puzzle's dense format, so that parsing it gives back the
+    /// same map. Only meaningful while the files are still in ID order.
+    pub fn dense(&self) -> String {
+        let mut dense = String::new();
+        let mut expecting_file = true;
+        for span in &self.spans {
+            if span.is_file() != expecting_file {
+                dense.push('0');
+            } else {
+                expecting_file = !expecting_file;
+            }
+            dense.push_str(&span.length().to_string());
+        }
+        dense
+    }
+
+    /// The sum over every file block of its position times its file ID.
+    pub fn checksum(&self) -> u64 {
+        let mut position = 0;
+        let mut checksum = 0;
+        for span in &self.spans {
+            if let Span::File { id, length } = span {
+                checksum += checksum_of(*id, position, *length);
+            }
+            position += span.length();
+        }
+        checksum
+    }
+
+    /// Adds a span to the end, merging it into the last one if they hold the
+    /// same thing.
+    fn push(&mut self, span: Span) {
+        match (self.spans.last_mut(), span) {
+            (_, span) if span.length() == 0 => {}
+            (Some(Span::Free { length }), Span::Free { length: extra }) => *length += extra,
+            (
+                Some(Span::File { id, length }),
+                Span::File {
+                    id: other,
+                    length: extra,
+                },
+            ) if *id == other => *length += extra,
+            _ => self.spans.push(span),
+        }
+    }
+
+    /// Moves file blocks one at a time from the end of the disk into the
+    /// leftmost free block, until there are no gaps between files.
+    pub fn compact_blocks(&self) -> Self {
+        let spans = &self.spans;
+        let Some(mut right) = spans.iter().rposition(Span::is_file) else {
+            return self.clone();
         };
-        while !is_compacted(parsed_input.clone()) {
-            let first_empty_block = parsed_input.iter().position(|id| *id == -1).unwrap();
-            let last_file_block = parsed_input.iter().rposition(|id| *id != -1).unwrap();
-            parsed_input.swap(first_empty_block, last_file_block);
+
+        // Walk forwards through the spans, filling each free span from the
+        // file at the back, until the two ends meet.
+        let mut compacted = Self::default();
+        let mut right_remaining = spans[right].length();
+        let mut left = 0;
+        while left <= right {
+            match spans[left] {
+                Span::File { id, length } => {
+                    let length = if left == right {
+                        right_remaining
+                    } else {
+                        length
+                    };
+                    compacted.push(Span::File { id, length });
+                }
+                Span::Free { length } => {
+                    let mut free = length;
+                    while free > 0 && left < right {
+                        let Span::File { id, .. } = spans[right] else {
+                            unreachable!("The right end should always be a file")
+                        };
+                        if right_remaining == 0 {
+                            match spans[..right].iter().rposition(Span::is_file) {
+                                Some(previous) => right = previous,
+                                None => break,
+                            }
+                            right_remaining = spans[right].length();
+                            continue;
+                        }
+                        let moved = free.min(right_remaining);
+                        compacted.push(Span::File { id, length: moved });
+                        free -= moved;
+                        right_remaining -= moved;
+                    }
+                }
+            }
+            left += 1;
         }
-        parsed_input
-            .into_iter()
-            .filter(|id| **id != -1)
-            .enumerate()
-            .fold(0, |acc, (idx, id)| acc + idx as i64 * *id)
-            .to_string()
+        compacted.push(Span::Free {
+            length: self.size() - compacted.size(),
+        });
+        compacted
     }
 
-    fn part_two(_parsed_input: &mut Self::ParsedInput) -> String {
-        // TODO: implement part two
-        0.to_string()
+    /// Moves each whole file once, in decreasing ID order, into the leftmost
+    /// free span which fits it, if there's one to its left.
+    pub fn compact_files(&self) -> Self {
+        // The start and length of every file and free span. Empty files have
+        // no blocks to move, and leave the free spans either side of them
+        // touching, so they're merged.
+        let mut files = vec![];
+        let mut free: Vec<(u64, u64)> = vec![];
+        let mut position = 0;
+        for span in &self.spans {
+            match span {
+                Span::File { id, length } if *length > 0 => files.push((position, *id, *length)),
+                Span::Free { length } if *length > 0 => match free.last_mut() {
+                    Some((start, previous)) if *start + *previous == position => {
+                        *previous += length
+                    }
+                    _ => free.push((position, *length)),
+                },
+                _ => {}
+            }
+            position += span.length();
+        }
+
+        // A free-list per span length, with the leftmost span of each length at
+        // the top of its heap
+        let longest = free.iter().map(|(_, length)| *length).max().unwrap_or(0);
+        let mut free_spans = vec![BinaryHeap::new(); longest as usize + 1];
+        for (start, length) in free {
+            free_spans[length as usize].push(Reverse(start));
+        }
+
+        files.sort_by_key(|(_, id, _)| Reverse(*id));
+        for (file_start, _, length) in files.iter_mut() {
+            let leftmost_fit = (*length as usize..free_spans.len())
+                .filter_map(|span_length| {
+                    free_spans[span_length]
+                        .peek()
+                        .map(|Reverse(start)| (*start, span_length))
+                })
+                .filter(|(start, _)| start < file_start)
+                .min();
+            if let Some((start, span_length)) = leftmost_fit {
+                free_spans[span_length].pop();
+                let remaining = span_length - *length as usize;
+                if remaining > 0 {
+                    free_spans[remaining].push(Reverse(start + *length));
+                }
+                *file_start = start;
+            }
+        }
+
+        files.sort_unstable();
+        let mut compacted = Self::default();
+        let mut position = 0;
+        for (start, id, length) in files {
+            compacted.push(Span::Free {
+                length: start - position,
+            });
+            compacted.push(Span::File { id, length });
+            position = start + length;
+        }
+        compacted.push(Span::Free {
+            length: self.size() - position,
+        });
+        compacted
+    }
+}
+
+impl Solution for Day09 {
+    type ParsedInput = DiskMap;
+
+    fn parse_input(input_lines: &str) -> Self::ParsedInput {
+        input_lines.parse().expect("Couldn't parse input")
+    }
+
+    fn part_one(parsed_input: &mut Self::ParsedInput) -> String {
+        parsed_input.compact_blocks().checksum().to_string()
+    }
+
+    fn part_two(parsed_input: &mut Self::ParsedInput) -> String {
+        parsed_input.compact_files().checksum().to_string()
     }
 }
 
 #[cfg(test)]
 mod tests {
+
     use super::*;
 
     #[test]
@@ -65,9 +323,59 @@ mod tests {
         )
     }
 
+    #[test]
+    fn check_day09_part1_no_free_space() {
+        assert_eq!(Day09::solve_part_one("10101"), "5".to_string());
+        assert_eq!(Day09::solve_part_one("1"), "0".to_string());
+    }
+
     #[test]
     fn check_day09_part2_case1() {
-        assert_eq!(Day09::solve_part_two(""), "0".to_string())
+        assert_eq!(
+            Day09::solve_part_two("2333133121414131402"),
+            "2858".to_string()
+        )
+    }
+
+    #[test]
+    fn check_disk_map_display_and_round_trip() {
+        let disk_map: DiskMap = "2333133121414131402".parse().unwrap();
+        assert_eq!(
+            disk_map.to_string(),
+            "00...111...2...333.44.5555.6666.777.888899"
+        );
+        assert_eq!(disk_map.dense(), "2333133121414131402");
+        assert_eq!("12345".parse::<DiskMap>().unwrap().dense(), "12345");
+        assert_eq!(disk_map.checksum(), 4116);
+        assert_eq!(
+            "12a4".parse::<DiskMap>(),
+            Err(ParseDiskMapError {
+                position: 2,
+                character: 'a'
+            })
+        );
+    }
+
+    #[test]
+    fn check_disk_map_compaction() {
+        let disk_map: DiskMap = "2333133121414131402".parse().unwrap();
+        let by_blocks = disk_map.compact_blocks();
+        assert_eq!(
+            by_blocks.to_string(),
+            "0099811188827773336446555566.............."
+        );
+        assert_eq!(by_blocks.checksum(), 1928);
+        let by_files = disk_map.compact_files();
+        assert_eq!(
+            by_files.to_string(),
+            "00992111777.44.333....5555.6666.....8888.."
+        );
+        assert_eq!(by_files.checksum(), 2858);
+
+        let disk_map: DiskMap = "12345".parse().unwrap();
+        assert_eq!(disk_map.to_string(), "0..111....22222");
+        assert_eq!(disk_map.compact_blocks().to_string(), "022111222......");
+        assert_eq!(disk_map.compact_files(), disk_map);
     }
 
     #[test]
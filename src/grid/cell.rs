@@ -0,0 +1,52 @@
+use std::{error::Error, fmt::Display};
+
+/// An element drawn as a single character of a puzzle grid.
+///
+/// Usually derived with `#[derive(GridCell)]`, annotating each unit variant of
+/// an enum with its character, e.g. `#[cell('#')]`, so that parsing and
+/// rendering are generated from the same mapping. The derive also provides
+/// matching [`Display`], [`std::str::FromStr`] and `TryFrom<char>`
+/// implementations.
+pub trait GridCell: Sized {
+    /// The element drawn as a character, if there is one.
+    fn from_char(character: char) -> Option<Self>;
+    /// The character this element is drawn as.
+    fn to_char(&self) -> char;
+}
+
+/// The input did not match the character of any element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCellError(pub String);
+
+impl Display for ParseCellError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unrecognised grid cell {:?}", self.0)
+    }
+}
+
+impl Error for ParseCellError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Direction, Grid, GridCell};
+
+    #[derive(Debug, PartialEq, Eq, GridCell)]
+    enum Tile {
+        #[cell('#')]
+        Wall,
+        #[cell('.')]
+        Floor,
+        Arrow(Direction),
+    }
+
+    #[test]
+    fn check_derived_mapping_round_trips() {
+        let input = "#.^\n<v>\n";
+        let grid: Grid<Tile> = input.parse().unwrap();
+        assert_eq!(grid.get(0, 2), Some(&Tile::Arrow(Direction::Up)));
+        assert_eq!(grid.to_string(), input);
+        assert_eq!(Tile::try_from('x'), Err(ParseCellError("x".to_string())));
+        assert_eq!("##".parse::<Tile>(), Err(ParseCellError("##".to_string())));
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    fs::{create_dir_all, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use super::Grid;
+
+/// A colour as red, green and blue components.
+pub type Rgb = [u8; 3];
+
+/// A raster image of a grid, with each cell drawn as a square block of pixels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    scale: usize,
+    pixels: Vec<u8>,
+}
+
+impl<T> Grid<T> {
+    /// Draws the grid as an image, colouring each cell with a caller-supplied
+    /// palette and drawing it `scale` pixels square.
+    pub fn to_image<F>(&self, mut palette: F, scale: usize) -> Image
+    where
+        F: FnMut(&T) -> Rgb,
+    {
+        let (rows, cols) = self.dimensions();
+        let mut image = Image {
+            width: cols * scale,
+            height: rows * scale,
+            scale,
+            pixels: vec![0; rows * cols * scale * scale * 3],
+        };
+        for (pos, element) in self.iter_cells() {
+            image.fill_cell(pos, palette(element));
+        }
+        image
+    }
+}
+
+impl Image {
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// The colour of a pixel, counting from the top-left corner.
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Rgb> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let offset = (y * self.width + x) * 3;
+        self.pixels[offset..offset + 3].try_into().ok()
+    }
+
+    fn fill_cell(&mut self, pos: (usize, usize), colour: Rgb) {
+        let (row_index, col_index) = pos;
+        if (row_index + 1) * self.scale > self.height || (col_index + 1) * self.scale > self.width {
+            return;
+        }
+        for y in row_index * self.scale..(row_index + 1) * self.scale {
+            let start = (y * self.width + col_index * self.scale) * 3;
+            for pixel in self.pixels[start..start + self.scale * 3].chunks_exact_mut(3) {
+                pixel.copy_from_slice(&colour);
+            }
+        }
+    }
+
+    /// Recolours the cells at the given grid positions, e.g. to overlay a path
+    /// or a set of highlighted positions. Positions outside the grid are ignored.
+    pub fn paint<I>(&mut self, positions: I, colour: Rgb)
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        for pos in positions {
+            self.fill_cell(pos, colour);
+        }
+    }
+
+    /// Writes the image in the binary PPM (P6) format.
+    pub fn write_ppm<W: Write>(&self, mut out: W) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.pixels)?;
+        out.flush()
+    }
+
+    /// Writes the image in the PNG format.
+    pub fn write_png<W: Write>(&self, out: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(&self.pixels)
+            .map_err(io::Error::other)?;
+        writer.finish().map_err(io::Error::other)
+    }
+
+    /// Saves the image to a file, as PPM if the path has a `.ppm` extension
+    /// and as PNG otherwise.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let out = BufWriter::new(File::create(path)?);
+        if path.extension().is_some_and(|extension| extension == "ppm") {
+            self.write_ppm(out)
+        } else {
+            self.write_png(out)
+        }
+    }
+}
+
+/// Saves a sequence of images as numbered PNG files in a directory, for
+/// stitching into an animation with an external tool.
+pub struct FrameWriter {
+    directory: PathBuf,
+    frames: usize,
+}
+
+impl FrameWriter {
+    /// Creates the directory if it does not already exist.
+    pub fn new<P: Into<PathBuf>>(directory: P) -> io::Result<Self> {
+        let directory = directory.into();
+        create_dir_all(&directory)?;
+        Ok(Self {
+            directory,
+            frames: 0,
+        })
+    }
+
+    /// Saves the next frame, returning the path it was written to.
+    pub fn write(&mut self, image: &Image) -> io::Result<PathBuf> {
+        let path = self.directory.join(format!("frame_{:05}.png", self.frames));
+        image.save(&path)?;
+        self.frames += 1;
+        Ok(path)
+    }
+
+    /// The number of frames written so far.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgb = [0, 0, 0];
+    const WHITE: Rgb = [255, 255, 255];
+    const RED: Rgb = [255, 0, 0];
+
+    #[test]
+    fn check_to_image_and_paint() {
+        let grid: Grid<char> = "#.\n..".parse().unwrap();
+        let mut image = grid.to_image(|ch| if *ch == '#' { BLACK } else { WHITE }, 2);
+        assert_eq!(image.dimensions(), (4, 4));
+        assert_eq!(image.pixel(1, 1), Some(BLACK));
+        assert_eq!(image.pixel(2, 1), Some(WHITE));
+        image.paint([(1, 1), (5, 5)], RED);
+        assert_eq!(image.pixel(3, 3), Some(RED));
+        assert_eq!(image.pixel(4, 0), None);
+    }
+
+    #[test]
+    fn check_write_ppm_and_png() {
+        let grid: Grid<char> = "#.\n..".parse().unwrap();
+        let image = grid.to_image(|ch| if *ch == '#' { BLACK } else { WHITE }, 2);
+        let mut ppm = vec![];
+        image.write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+
+        let mut png = vec![];
+        image.write_png(&mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
@@ -0,0 +1,546 @@
+use std::{
+    fmt::Display,
+    ops::{
+        Index, IndexMut, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+    },
+    slice::Iter,
+    str::FromStr,
+};
+
+mod cell;
+mod image;
+mod parse;
+mod path;
+mod pattern;
+mod region;
+mod render;
+mod sparse;
+mod transform;
+
+pub use cell::{GridCell, ParseCellError};
+pub use grid_cell_derive::GridCell;
+pub use image::{FrameWriter, Image, Rgb};
+pub use parse::ParseGridError;
+pub use path::{manhattan_distance, SearchResult};
+pub use pattern::{Orientations, Pattern};
+pub use region::Region;
+pub use render::{Colour, Renderer};
+pub use sparse::SparseGrid;
+pub use transform::Rect;
+
+/// How a [`Grid`] behaves at its edges when walking or looking at neighbours.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Topology {
+    /// Stepping off an edge leaves the grid.
+    #[default]
+    Bounded,
+    /// Stepping off an edge re-enters on the opposite edge, as on a torus.
+    Wrapping,
+    /// Stepping off an edge stays put on the edge.
+    Clamped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T>(Vec<Vec<T>>, Topology);
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in &self.0 {
+            for element in row {
+                f.write_str(&element.to_string())?;
+            }
+            f.write_str("\n")?
+        }
+        Ok(())
+    }
+}
+
+impl<T: FromStr> FromStr for Grid<T> {
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(
+            s.lines()
+                .map(|row| {
+                    row.chars()
+                        .map(|character| character.encode_utf8(&mut [0; 4]).parse())
+                        .collect::<Result<_, _>>()
+                })
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+}
+
+/// Row and column offsets of the four orthogonal neighbours of a cell, in
+/// the order up, down, left, right.
+const ORTHOGONAL_OFFSETS: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// Row and column offsets of all eight neighbours of a cell, in row-major order.
+const ALL_OFFSETS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+impl<T> Grid<T> {
+    /// Builds a bounded grid from its rows, which are assumed to be of equal length.
+    pub fn new(rows: Vec<Vec<T>>) -> Self {
+        Self(rows, Topology::default())
+    }
+
+    /// Sets how the grid behaves at its edges.
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.1 = topology;
+        self
+    }
+
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.1 = topology;
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.1
+    }
+
+    pub fn get(&self, row_index: usize, col_index: usize) -> Option<&T> {
+        self.0.get(row_index).and_then(|row| row.get(col_index))
+    }
+
+    pub fn get_pos(&self, pos: (usize, usize)) -> Option<&T> {
+        self.get(pos.0, pos.1)
+    }
+
+    pub fn get_mut(&mut self, row_index: usize, col_index: usize) -> Option<&mut T> {
+        self.0
+            .get_mut(row_index)
+            .and_then(|row| row.get_mut(col_index))
+    }
+
+    pub fn get_pos_mut(&mut self, pos: (usize, usize)) -> Option<&mut T> {
+        self.get_mut(pos.0, pos.1)
+    }
+
+    pub fn put(&mut self, row_index: usize, col_index: usize, element: T) -> bool {
+        self.get_mut(row_index, col_index)
+            .map(|old| *old = element)
+            .is_some()
+    }
+
+    /// Swaps the elements at two positions.
+    ///
+    /// # Panics
+    /// If either position is outside the grid, as for [`slice::swap`].
+    pub fn swap(&mut self, a: (usize, usize), b: (usize, usize)) {
+        if a.0 == b.0 {
+            self.0[a.0].swap(a.1, b.1);
+            return;
+        }
+        let (first, second) = if a.0 < b.0 { (a, b) } else { (b, a) };
+        let (head, tail) = self.0.split_at_mut(second.0);
+        std::mem::swap(&mut head[first.0][first.1], &mut tail[0][second.1]);
+    }
+
+    pub fn iter(&self) -> Iter<'_, Vec<T>> {
+        self.0.iter()
+    }
+
+    /// Iterates over every element in row-major order, with its position.
+    pub fn iter_cells(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.0.iter().enumerate().flat_map(|(row_index, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(col_index, element)| ((row_index, col_index), element))
+        })
+    }
+
+    /// Iterates mutably over every element in row-major order, with its position.
+    pub fn iter_cells_mut(&mut self) -> impl Iterator<Item = ((usize, usize), &mut T)> {
+        self.0.iter_mut().enumerate().flat_map(|(row_index, row)| {
+            row.iter_mut()
+                .enumerate()
+                .map(move |(col_index, element)| ((row_index, col_index), element))
+        })
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        // Assume rectangular, but cope with empty data structure
+        (self.0.len(), self.0.first().map_or(0, Vec::len))
+    }
+
+    /// Convenience function for row by index.
+    pub fn row(&self, row_index: usize) -> Option<Vec<&T>> {
+        self.0.get(row_index).map(|row| row.iter().collect())
+    }
+
+    /// Constructs the column of this grid specified by the column index
+    /// and returns it.
+    pub fn column(&self, col_index: usize) -> Option<Vec<&T>> {
+        self.iter().map(|row| row.get(col_index)).collect()
+    }
+
+    /// Searches for the first (row-major ordering) element contained in
+    /// the grid that satisfies a predicate, and return it with its location
+    /// if found.
+    ///
+    /// Compare [`Iterator::find`], which does the same thing over a normal
+    /// [Iterator] (without returning index) - this wraps that operation
+    /// with [`Iterator::enumerate`] over two dimensions to return the position.
+    /// Compare also [`Iterator::position`], but this does not return the element
+    /// itself (though it could be subsequently obtained).
+    pub fn locate<P>(&self, mut predicate: P) -> Option<(usize, usize, &T)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter()
+            .enumerate()
+            .filter_map(|(row_index, row)| {
+                row.iter()
+                    .enumerate()
+                    .find(|(_, element)| predicate(element))
+                    .map(|(col_index, element)| (row_index, col_index, element))
+            })
+            .next()
+    }
+
+    /// Return all elements (with their positions) contained in the grid that satisfiy a
+    /// predicate.
+    ///
+    /// Compare [`Iterator::filter`], which does the same thing over a normal
+    /// [Iterator] (without returning index) - this wraps that operation
+    /// with [`Iterator::enumerate`] over two dimensions to return the position.
+    pub fn locate_all<P>(&self, mut predicate: P) -> Vec<(usize, usize, &T)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter()
+            .enumerate()
+            .flat_map(|(row_index, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, element)| predicate(element))
+                    .map(|(col_index, element)| (row_index, col_index, element))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+impl<T> Grid<T> {
+    /// Steps one cell in a direction, returning the element reached with its
+    /// location. What happens at the edges depends on the grid's [`Topology`]:
+    /// a bounded grid returns `None` there.
+    pub fn walk(
+        &self,
+        row_index: usize,
+        col_index: usize,
+        direction: &Direction,
+    ) -> Option<(usize, usize, &T)> {
+        self.offset((row_index, col_index), direction.delta())
+    }
+
+    /// Returns the element offset from the given position by a signed number of
+    /// rows and columns, with its location, according to the grid's [`Topology`].
+    fn offset(&self, pos: (usize, usize), delta: (isize, isize)) -> Option<(usize, usize, &T)> {
+        let (row_index, col_index) = match self.1 {
+            Topology::Bounded => (
+                pos.0.checked_add_signed(delta.0)?,
+                pos.1.checked_add_signed(delta.1)?,
+            ),
+            Topology::Wrapping | Topology::Clamped => {
+                // Only meaningful from a position inside the (hence non-empty) grid
+                self.get_pos(pos)?;
+                let (rows, cols) = self.dimensions();
+                let (row_index, col_index) = (pos.0 as isize + delta.0, pos.1 as isize + delta.1);
+                if self.1 == Topology::Wrapping {
+                    (
+                        row_index.rem_euclid(rows as isize) as usize,
+                        col_index.rem_euclid(cols as isize) as usize,
+                    )
+                } else {
+                    (
+                        row_index.clamp(0, rows as isize - 1) as usize,
+                        col_index.clamp(0, cols as isize - 1) as usize,
+                    )
+                }
+            }
+        };
+        self.get(row_index, col_index)
+            .map(|el| (row_index, col_index, el))
+    }
+
+    /// Iterates over the (up to four) orthogonally adjacent elements of a position,
+    /// with their locations. Neighbours are found as for [`Grid::walk`]; those
+    /// falling outside the grid, or back on the position itself, are skipped.
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize, &T)> {
+        ORTHOGONAL_OFFSETS
+            .into_iter()
+            .filter_map(move |delta| self.offset(pos, delta))
+            .filter(move |(row_index, col_index, _)| (*row_index, *col_index) != pos)
+    }
+
+    /// Iterates over the (up to eight) orthogonally and diagonally adjacent elements
+    /// of a position, with their locations. Neighbours are found as for
+    /// [`Grid::walk`]; those falling outside the grid, or back on the position
+    /// itself, are skipped.
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize, &T)> {
+        ALL_OFFSETS
+            .into_iter()
+            .filter_map(move |delta| self.offset(pos, delta))
+            .filter(move |(row_index, col_index, _)| (*row_index, *col_index) != pos)
+    }
+
+    /// Iterates (in row-major order of their top-left corners) over every
+    /// `height` x `width` sub-view that fits entirely within the grid.
+    ///
+    /// Compare [`slice::windows`], which does the same thing in one dimension.
+    /// Yields nothing if the window is larger than the grid in either dimension.
+    pub fn windows(&self, height: usize, width: usize) -> impl Iterator<Item = GridView<'_, T>> {
+        let (rows, cols) = self.dimensions();
+        let row_starts = if height > 0 {
+            (rows + 1).saturating_sub(height)
+        } else {
+            0
+        };
+        let col_starts = if width > 0 {
+            (cols + 1).saturating_sub(width)
+        } else {
+            0
+        };
+        (0..row_starts).flat_map(move |row_index| {
+            (0..col_starts).map(move |col_index| GridView {
+                grid: self,
+                origin: (row_index, col_index),
+                dimensions: (height, width),
+            })
+        })
+    }
+}
+
+/// A borrowed rectangular region of a [`Grid`], as yielded by [`Grid::windows`].
+///
+/// Positions are relative to the view's top-left corner; anything outside the
+/// view is treated as absent even if the underlying grid has an element there.
+#[derive(Clone, Copy)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    origin: (usize, usize),
+    dimensions: (usize, usize),
+}
+
+impl<'a, T> GridView<'a, T> {
+    pub fn get(&self, row_index: usize, col_index: usize) -> Option<&'a T> {
+        if row_index >= self.dimensions.0 || col_index >= self.dimensions.1 {
+            return None;
+        }
+        self.grid
+            .get(self.origin.0 + row_index, self.origin.1 + col_index)
+    }
+
+    pub fn get_pos(&self, pos: (usize, usize)) -> Option<&'a T> {
+        self.get(pos.0, pos.1)
+    }
+
+    /// Position of the view's top-left corner in the underlying grid.
+    pub fn origin(&self) -> (usize, usize) {
+        self.origin
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        self.dimensions
+    }
+}
+
+// Indexing by row or range of rows works as for the underlying `Vec`. A
+// blanket impl over `SliceIndex` would overlap with the `(usize, usize)` impl
+// below as far as the compiler is concerned, so each index type is listed.
+macro_rules! impl_row_index {
+    ($($index:ty => $output:ty),* $(,)?) => {
+        $(
+            impl<T> Index<$index> for Grid<T> {
+                type Output = $output;
+
+                fn index(&self, index: $index) -> &Self::Output {
+                    &self.0[index]
+                }
+            }
+
+            impl<T> IndexMut<$index> for Grid<T> {
+                fn index_mut(&mut self, index: $index) -> &mut Self::Output {
+                    &mut self.0[index]
+                }
+            }
+        )*
+    };
+}
+
+impl_row_index!(
+    usize => Vec<T>,
+    Range<usize> => [Vec<T>],
+    RangeFrom<usize> => [Vec<T>],
+    RangeTo<usize> => [Vec<T>],
+    RangeInclusive<usize> => [Vec<T>],
+    RangeToInclusive<usize> => [Vec<T>],
+    RangeFull => [Vec<T>],
+);
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &Self::Output {
+        &self.0[pos.0][pos.1]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut Self::Output {
+        &mut self.0[pos.0][pos.1]
+    }
+}
+
+impl<T> IntoIterator for Grid<T> {
+    type Item = Vec<T>;
+    type IntoIter = <Vec<Vec<T>> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, GridCell)]
+pub enum Direction {
+    #[cell('^')]
+    Up,
+    #[cell('v')]
+    Down,
+    #[cell('<')]
+    Left,
+    #[cell('>')]
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Self; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+
+    /// The row and column offset of one step in this direction.
+    pub fn delta(&self) -> (isize, isize) {
+        match self {
+            Self::Up => (-1, 0),
+            Self::Down => (1, 0),
+            Self::Left => (0, -1),
+            Self::Right => (0, 1),
+        }
+    }
+
+    pub fn turn_right(&self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_neighbours4_at_corner() {
+        let grid: Grid<u8> = "123
+456
+789"
+        .parse()
+        .unwrap();
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0, &4), (0, 1, &2)]
+        )
+    }
+
+    #[test]
+    fn check_neighbours8_in_centre() {
+        let grid: Grid<u8> = "123
+456
+789"
+        .parse()
+        .unwrap();
+        assert_eq!(
+            grid.neighbours8((1, 1))
+                .map(|(_, _, el)| *el)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 6, 7, 8, 9]
+        )
+    }
+
+    #[test]
+    fn check_cell_iteration_and_mutation() {
+        let mut grid: Grid<u8> = "123
+456
+789"
+        .parse()
+        .unwrap();
+        for (pos, element) in grid.iter_cells_mut() {
+            if pos.0 == pos.1 {
+                *element = 0;
+            }
+        }
+        grid.swap((0, 2), (2, 0));
+        grid[(1, 0)] = 9;
+        *grid.get_mut(1, 2).unwrap() += 1;
+        assert_eq!(grid.to_string(), "027\n907\n380\n");
+        assert_eq!(
+            grid.iter_cells().find(|(_, element)| **element == 8),
+            Some(((2, 1), &8))
+        );
+        assert_eq!(grid[2], vec![3, 8, 0]);
+        assert_eq!(grid[1..].len(), 2);
+        assert_eq!(grid[..=0], [vec![0, 2, 7]]);
+        grid[..2].swap(0, 1);
+        assert_eq!(
+            grid.to_string(),
+            "907
+027
+380
+"
+        );
+    }
+
+    #[test]
+    fn check_walk_with_topology() {
+        let mut grid: Grid<u8> = "123
+456
+789"
+        .parse()
+        .unwrap();
+        assert_eq!(grid.walk(0, 0, &Direction::Up), None);
+        grid.set_topology(Topology::Wrapping);
+        assert_eq!(grid.walk(0, 0, &Direction::Up), Some((2, 0, &7)));
+        assert_eq!(grid.walk(1, 2, &Direction::Right), Some((1, 0, &4)));
+        assert_eq!(grid.neighbours8((0, 0)).count(), 8);
+        grid.set_topology(Topology::Clamped);
+        assert_eq!(grid.walk(0, 0, &Direction::Left), Some((0, 0, &1)));
+        assert_eq!(grid.neighbours4((0, 0)).count(), 2);
+        assert_eq!(grid.walk(3, 0, &Direction::Up), None);
+    }
+
+    #[test]
+    fn check_windows() {
+        let grid: Grid<u8> = "123
+456
+789"
+        .parse()
+        .unwrap();
+        let windows = grid.windows(2, 2).collect::<Vec<_>>();
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[3].origin(), (1, 1));
+        assert_eq!(windows[3].get(1, 1), Some(&9));
+        assert_eq!(windows[0].get(2, 0), None);
+        assert_eq!(grid.windows(4, 1).count(), 0);
+    }
+}
@@ -0,0 +1,125 @@
+use std::{error::Error, fmt::Display};
+
+use super::Grid;
+
+/// Why a [`Grid`] could not be parsed by [`Grid::parse_with`]. Rows and
+/// columns are counted from zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGridError<E> {
+    /// A character could not be converted into an element.
+    Cell {
+        row: usize,
+        column: usize,
+        character: char,
+        error: E,
+    },
+    /// A row is a different length from the first row.
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl<E: Display> Display for ParseGridError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cell {
+                row,
+                column,
+                character,
+                error,
+            } => write!(
+                f,
+                "invalid character {character:?} at row {row}, column {column}: {error}"
+            ),
+            Self::Ragged {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {row} has {found} characters, but the first row has {expected}"
+            ),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for ParseGridError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Cell { error, .. } => Some(error),
+            Self::Ragged { .. } => None,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parses a grid with one element per character, converting each with a
+    /// caller-supplied function. Unlike the [`std::str::FromStr`]
+    /// implementation, failures report where they happened, and rows of
+    /// differing lengths are rejected.
+    pub fn parse_with<F, E>(input: &str, mut convert: F) -> Result<Self, ParseGridError<E>>
+    where
+        F: FnMut(char) -> Result<T, E>,
+    {
+        let mut rows: Vec<Vec<T>> = vec![];
+        for (row_index, line) in input.lines().enumerate() {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(col_index, character)| {
+                    convert(character).map_err(|error| ParseGridError::Cell {
+                        row: row_index,
+                        column: col_index,
+                        character,
+                        error,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if let Some(first) = rows.first() {
+                if row.len() != first.len() {
+                    return Err(ParseGridError::Ragged {
+                        row: row_index,
+                        expected: first.len(),
+                        found: row.len(),
+                    });
+                }
+            }
+            rows.push(row);
+        }
+        Ok(Self::new(rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digit(ch: char) -> Result<u32, char> {
+        ch.to_digit(10).ok_or(ch)
+    }
+
+    #[test]
+    fn check_parse_with() {
+        let grid = Grid::parse_with("12\n34", digit).unwrap();
+        assert_eq!(grid.to_string(), "12\n34\n");
+        assert_eq!(
+            Grid::parse_with("12\n3x", digit),
+            Err(ParseGridError::Cell {
+                row: 1,
+                column: 1,
+                character: 'x',
+                error: 'x'
+            })
+        );
+        assert_eq!(
+            Grid::parse_with("12\n345", digit),
+            Err(ParseGridError::Ragged {
+                row: 1,
+                expected: 2,
+                found: 3
+            })
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+use super::{Direction, Grid};
+
+/// Distances and predecessors found by a search from a single start position.
+///
+/// Only positions reached by the search are present; the start has distance
+/// zero and no predecessor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub start: (usize, usize),
+    pub distances: HashMap<(usize, usize), u64>,
+    pub predecessors: HashMap<(usize, usize), (usize, usize)>,
+}
+
+impl SearchResult {
+    fn new(start: (usize, usize)) -> Self {
+        Self {
+            start,
+            distances: HashMap::from([(start, 0)]),
+            predecessors: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, pos: (usize, usize)) -> Option<u64> {
+        self.distances.get(&pos).copied()
+    }
+
+    /// Reconstructs the path from the start to a position by following
+    /// predecessors back, returning it start first, or `None` if the position
+    /// was not reached.
+    pub fn path_to(&self, pos: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        if !self.distances.contains_key(&pos) {
+            return None;
+        }
+        let mut path = vec![pos];
+        let mut cursor = pos;
+        while let Some(previous) = self.predecessors.get(&cursor) {
+            path.push(*previous);
+            cursor = *previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Steps from a position in each [`Direction`], yielding the positions
+    /// reached together with the elements being left and entered.
+    fn moves(&self, pos: (usize, usize)) -> impl Iterator<Item = ((usize, usize), &T, &T)> {
+        let current = self.get_pos(pos);
+        Direction::ALL.into_iter().filter_map(move |direction| {
+            let (row_index, col_index, next) = self.walk(pos.0, pos.1, &direction)?;
+            Some(((row_index, col_index), current?, next))
+        })
+    }
+
+    /// Breadth-first search from `start`, where each step has unit cost.
+    ///
+    /// `passable` is called with the elements being left and entered, and
+    /// decides whether that step may be taken.
+    pub fn bfs<P>(&self, start: (usize, usize), mut passable: P) -> SearchResult
+    where
+        P: FnMut(&T, &T) -> bool,
+    {
+        let mut result = SearchResult::new(start);
+        let mut queue = VecDeque::from([start]);
+        while let Some(pos) = queue.pop_front() {
+            let distance = result.distances[&pos];
+            for (next, from, to) in self.moves(pos) {
+                if result.distances.contains_key(&next) || !passable(from, to) {
+                    continue;
+                }
+                result.distances.insert(next, distance + 1);
+                result.predecessors.insert(next, pos);
+                queue.push_back(next);
+            }
+        }
+        result
+    }
+
+    /// Dijkstra's algorithm from `start`, finding the cheapest route to every
+    /// reachable position.
+    ///
+    /// `cost` is called with the elements being left and entered, and returns
+    /// the cost of that step, or `None` if it may not be taken.
+    pub fn dijkstra<C>(&self, start: (usize, usize), cost: C) -> SearchResult
+    where
+        C: FnMut(&T, &T) -> Option<u64>,
+    {
+        self.best_first(start, None, cost, |_| 0)
+    }
+
+    /// A* search from `start` to `goal`, stopping as soon as the goal is
+    /// settled. The goal's distance and path can then be read from the result,
+    /// and are absent if it is unreachable.
+    ///
+    /// `cost` is as for [`Grid::dijkstra`]. `heuristic` estimates the remaining
+    /// cost from a position to the goal, and must never overestimate it for
+    /// the result to be the cheapest.
+    pub fn astar<C, H>(
+        &self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        cost: C,
+        heuristic: H,
+    ) -> SearchResult
+    where
+        C: FnMut(&T, &T) -> Option<u64>,
+        H: FnMut((usize, usize)) -> u64,
+    {
+        self.best_first(start, Some(goal), cost, heuristic)
+    }
+
+    fn best_first<C, H>(
+        &self,
+        start: (usize, usize),
+        goal: Option<(usize, usize)>,
+        mut cost: C,
+        mut heuristic: H,
+    ) -> SearchResult
+    where
+        C: FnMut(&T, &T) -> Option<u64>,
+        H: FnMut((usize, usize)) -> u64,
+    {
+        let mut result = SearchResult::new(start);
+        let mut queue = BinaryHeap::from([Reverse((heuristic(start), 0, start))]);
+        while let Some(Reverse((_, distance, pos))) = queue.pop() {
+            if distance > result.distances[&pos] {
+                // Stale entry, superseded by a cheaper route found later
+                continue;
+            }
+            if goal == Some(pos) {
+                break;
+            }
+            for (next, from, to) in self.moves(pos) {
+                let Some(step) = cost(from, to) else {
+                    continue;
+                };
+                let next_distance = distance + step;
+                if result
+                    .distances
+                    .get(&next)
+                    .is_some_and(|known| *known <= next_distance)
+                {
+                    continue;
+                }
+                result.distances.insert(next, next_distance);
+                result.predecessors.insert(next, pos);
+                queue.push(Reverse((
+                    next_distance + heuristic(next),
+                    next_distance,
+                    next,
+                )));
+            }
+        }
+        result
+    }
+}
+
+/// The Manhattan distance between two positions, a suitable A* heuristic when
+/// every step costs at least one.
+pub fn manhattan_distance(a: (usize, usize), b: (usize, usize)) -> u64 {
+    (a.0.abs_diff(b.0) + a.1.abs_diff(b.1)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_bfs() {
+        let grid: Grid<char> = "S.#.
+.##.
+...E"
+            .parse()
+            .unwrap();
+        let result = grid.bfs((0, 0), |_, to| *to != '#');
+        assert_eq!(result.distance((2, 3)), Some(5));
+        assert_eq!(result.distance((0, 3)), Some(7));
+        assert_eq!(result.distance((0, 2)), None);
+        assert_eq!(
+            result.path_to((2, 3)),
+            Some(vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (2, 3)])
+        );
+    }
+
+    #[test]
+    fn check_dijkstra_with_costs() {
+        let grid: Grid<u8> = "19
+11"
+        .parse()
+        .unwrap();
+        let result = grid.dijkstra((0, 0), |_, to| Some(*to as u64));
+        assert_eq!(result.distance((1, 1)), Some(2));
+        assert_eq!(result.path_to((1, 1)), Some(vec![(0, 0), (1, 0), (1, 1)]));
+        assert_eq!(result.distance((0, 1)), Some(9));
+    }
+
+    #[test]
+    fn check_astar() {
+        let grid: Grid<char> = "S.#.
+.##.
+...E"
+            .parse()
+            .unwrap();
+        let goal = (2, 3);
+        let passable = |_: &char, to: &char| (*to != '#').then_some(1);
+        let result = grid.astar((0, 0), goal, passable, |pos| manhattan_distance(pos, goal));
+        assert_eq!(result.distance(goal), Some(5));
+        assert_eq!(result.path_to(goal).map(|path| path.len()), Some(6));
+        let result = grid.astar((0, 0), (0, 2), passable, |_| 0);
+        assert_eq!(result.path_to((0, 2)), None);
+    }
+}
@@ -0,0 +1,201 @@
+use super::{Grid, GridView};
+
+/// Which orientations of a [`Pattern`] to try when searching a grid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Orientations {
+    /// Only the pattern exactly as given.
+    Fixed,
+    /// The pattern and its rotations by 90, 180 and 270 degrees.
+    Rotations,
+    /// All eight rotations and reflections of the pattern.
+    All,
+}
+
+/// A small rectangular stencil to search for in a [`Grid`]. Cells holding
+/// `None` are wildcards and match any element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern<T>(Grid<Option<T>>);
+
+impl<T> Pattern<T> {
+    /// Builds a pattern from rows of cells, where `None` is a wildcard.
+    ///
+    /// # Panics
+    /// If the rows are not all the same length.
+    pub fn new(cells: Vec<Vec<Option<T>>>) -> Self {
+        let width = cells.first().map_or(0, Vec::len);
+        assert!(
+            cells.iter().all(|row| row.len() == width),
+            "Pattern rows must all be the same length"
+        );
+        Self(Grid::new(cells))
+    }
+
+    /// A single row reading the word left to right.
+    pub fn word<I: IntoIterator<Item = T>>(word: I) -> Self {
+        Self::new(vec![word.into_iter().map(Some).collect()])
+    }
+
+    /// A square reading the word diagonally from the top-left corner to the
+    /// bottom-right, with every other cell a wildcard.
+    pub fn diagonal_word<I: IntoIterator<Item = T>>(word: I) -> Self {
+        let letters = word.into_iter().collect::<Vec<_>>();
+        let size = letters.len();
+        Self::new(
+            letters
+                .into_iter()
+                .enumerate()
+                .map(|(index, letter)| {
+                    let mut row = (0..size).map(|_| None).collect::<Vec<_>>();
+                    row[index] = Some(letter);
+                    row
+                })
+                .collect(),
+        )
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        self.0.dimensions()
+    }
+
+    /// Checks whether the pattern matches a window of the same dimensions.
+    fn matches(&self, window: &GridView<'_, T>) -> bool
+    where
+        T: PartialEq,
+    {
+        self.0.iter().enumerate().all(|(row_index, row)| {
+            row.iter().enumerate().all(|(col_index, cell)| match cell {
+                None => true,
+                Some(expected) => window.get(row_index, col_index) == Some(expected),
+            })
+        })
+    }
+}
+
+impl<T: Clone> Pattern<T> {
+    /// Returns the distinct orientations of this pattern, starting with the
+    /// pattern itself. Symmetric patterns yield fewer than the maximum.
+    pub fn orientations(&self, orientations: Orientations) -> Vec<Self>
+    where
+        T: PartialEq,
+    {
+        let rotations = |cells: Grid<Option<T>>| {
+            let mut rotated = vec![cells];
+            for _ in 0..3 {
+                rotated.push(rotated.last().unwrap().rotate_cw());
+            }
+            rotated
+        };
+        let candidates = match orientations {
+            Orientations::Fixed => vec![self.0.clone()],
+            Orientations::Rotations => rotations(self.0.clone()),
+            Orientations::All => [
+                rotations(self.0.clone()),
+                rotations(self.0.flip_horizontal()),
+            ]
+            .concat(),
+        };
+
+        let mut distinct: Vec<Self> = vec![];
+        for candidate in candidates.into_iter().map(Self) {
+            if !distinct.contains(&candidate) {
+                distinct.push(candidate);
+            }
+        }
+        distinct
+    }
+}
+
+impl Pattern<char> {
+    /// Parses a pattern from lines of characters, treating `wildcard` as
+    /// matching anything.
+    pub fn from_stencil(stencil: &str, wildcard: char) -> Self {
+        Self::new(
+            stencil
+                .lines()
+                .map(|line| {
+                    line.chars()
+                        .map(|ch| if ch == wildcard { None } else { Some(ch) })
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<T: Clone + PartialEq> Grid<T> {
+    /// Finds every occurrence of a pattern in the grid, in any of the requested
+    /// orientations, returning the top-left corner of each matched region.
+    ///
+    /// A position appears once for each distinct orientation matching there, so
+    /// the length of the result is the number of matches.
+    pub fn find_pattern(
+        &self,
+        pattern: &Pattern<T>,
+        orientations: Orientations,
+    ) -> Vec<(usize, usize)> {
+        self.find_any(&pattern.orientations(orientations))
+    }
+
+    /// Finds every occurrence of a word reading in a straight line in any of
+    /// the eight directions - as in a word search - returning the top-left
+    /// corner of the region spanned by each match.
+    pub fn find_word<I: IntoIterator<Item = T>>(&self, word: I) -> Vec<(usize, usize)> {
+        let letters = word.into_iter().collect::<Vec<_>>();
+        let mut patterns = Pattern::word(letters.clone()).orientations(Orientations::All);
+        for diagonal in Pattern::diagonal_word(letters).orientations(Orientations::All) {
+            if !patterns.contains(&diagonal) {
+                patterns.push(diagonal);
+            }
+        }
+        self.find_any(&patterns)
+    }
+
+    fn find_any(&self, patterns: &[Pattern<T>]) -> Vec<(usize, usize)> {
+        patterns
+            .iter()
+            .flat_map(|pattern| {
+                let (height, width) = pattern.dimensions();
+                self.windows(height, width)
+                    .filter(|window| pattern.matches(window))
+                    .map(|window| window.origin())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_orientations_deduplicate() {
+        let pattern = Pattern::from_stencil("M.S\n.A.\nM.S", '.');
+        assert_eq!(pattern.orientations(Orientations::Fixed).len(), 1);
+        assert_eq!(pattern.orientations(Orientations::Rotations).len(), 4);
+        assert_eq!(pattern.orientations(Orientations::All).len(), 4);
+        assert_eq!(
+            Pattern::word("XMAS".chars())
+                .orientations(Orientations::All)
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn check_find_word() {
+        let grid: Grid<char> = "XMAS\nMM..\nA.A.\nS..S".parse().unwrap();
+        let mut matches = grid.find_word("XMAS".chars());
+        matches.sort();
+        assert_eq!(matches, vec![(0, 0), (0, 0), (0, 0)]);
+        assert_eq!(grid.find_word("SAMX".chars()).len(), 3);
+    }
+
+    #[test]
+    fn check_find_pattern_with_wildcards() {
+        let grid: Grid<char> = "M.M.\n.A..\nS.S.\n....".parse().unwrap();
+        let pattern = Pattern::from_stencil("M.S\n.A.\nM.S", '.');
+        assert_eq!(grid.find_pattern(&pattern, Orientations::Fixed), vec![]);
+        assert_eq!(grid.find_pattern(&pattern, Orientations::All), vec![(0, 0)]);
+    }
+}
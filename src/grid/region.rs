@@ -0,0 +1,243 @@
+use std::collections::BTreeSet;
+
+use super::{Direction, Grid, Rect, Topology};
+
+/// A connected set of grid positions, as found by [`Grid::flood_fill`] or
+/// [`Grid::components`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// Index of this region among those found by [`Grid::components`], in
+    /// row-major order of their first positions. Always zero from a flood fill.
+    pub label: usize,
+    pub positions: BTreeSet<(usize, usize)>,
+    /// The dimensions of the grid the region was found in, if that grid
+    /// wraps around, so that the region's edges are measured across its seams.
+    wrapping: Option<(usize, usize)>,
+}
+
+impl Region {
+    fn new<T>(positions: BTreeSet<(usize, usize)>, grid: &Grid<T>) -> Self {
+        Self {
+            label: 0,
+            positions,
+            wrapping: (grid.topology() == Topology::Wrapping).then(|| grid.dimensions()),
+        }
+    }
+
+    /// The position one step away in a direction. Only a wrapping grid's
+    /// extent matters; otherwise stepping off an edge just leaves the region.
+    fn neighbour(&self, pos: (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        if let Some((rows, cols)) = self.wrapping {
+            let (row_delta, col_delta) = direction.delta();
+            return Some((
+                (pos.0 as isize + row_delta).rem_euclid(rows as isize) as usize,
+                (pos.1 as isize + col_delta).rem_euclid(cols as isize) as usize,
+            ));
+        }
+        match direction {
+            Direction::Up => pos.0.checked_sub(1).map(|row_index| (row_index, pos.1)),
+            Direction::Down => Some((pos.0 + 1, pos.1)),
+            Direction::Left => pos.1.checked_sub(1).map(|col_index| (pos.0, col_index)),
+            Direction::Right => Some((pos.0, pos.1 + 1)),
+        }
+    }
+
+    pub fn area(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn contains(&self, pos: (usize, usize)) -> bool {
+        self.positions.contains(&pos)
+    }
+
+    /// Checks whether a position in the region has a boundary edge on the
+    /// given side, i.e. its neighbour in that direction is not in the region.
+    fn has_edge(&self, pos: (usize, usize), direction: Direction) -> bool {
+        self.contains(pos)
+            && !self
+                .neighbour(pos, direction)
+                .is_some_and(|next| self.contains(next))
+    }
+
+    /// The number of unit edges between a position in the region and one
+    /// outside it (including outside the grid, unless it wraps around).
+    pub fn perimeter(&self) -> usize {
+        self.positions
+            .iter()
+            .map(|pos| {
+                Direction::ALL
+                    .iter()
+                    .filter(|direction| self.has_edge(*pos, **direction))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// The number of straight sides of the region's boundary, where a run of
+    /// collinear unit edges facing the same way counts as one side. On a
+    /// wrapping grid, a side can run all the way round without any ends.
+    pub fn sides(&self) -> usize {
+        let ends: usize = self
+            .positions
+            .iter()
+            .map(|pos| {
+                Direction::ALL
+                    .iter()
+                    .filter(|direction| {
+                        // Count each side once, at its topmost or leftmost edge
+                        let along = match direction {
+                            Direction::Up | Direction::Down => Direction::Left,
+                            Direction::Left | Direction::Right => Direction::Up,
+                        };
+                        self.has_edge(*pos, **direction)
+                            && !self
+                                .neighbour(*pos, along)
+                                .is_some_and(|previous| self.has_edge(previous, **direction))
+                    })
+                    .count()
+            })
+            .sum();
+        // Sides which go all the way round have no topmost or leftmost edge
+        let rings: usize = self.wrapping.map_or(0, |(rows, cols)| {
+            Direction::ALL
+                .iter()
+                .map(|direction| match direction {
+                    Direction::Up | Direction::Down => (0..rows)
+                        .filter(|row| (0..cols).all(|col| self.has_edge((*row, col), *direction)))
+                        .count(),
+                    Direction::Left | Direction::Right => (0..cols)
+                        .filter(|col| (0..rows).all(|row| self.has_edge((row, *col), *direction)))
+                        .count(),
+                })
+                .sum()
+        });
+        ends + rings
+    }
+
+    /// The smallest rectangle containing every position in the region, or
+    /// `None` if the region is empty.
+    pub fn bounding_box(&self) -> Option<Rect> {
+        let top = self.positions.iter().map(|pos| pos.0).min()?;
+        let bottom = self.positions.iter().map(|pos| pos.0).max()?;
+        let left = self.positions.iter().map(|pos| pos.1).min()?;
+        let right = self.positions.iter().map(|pos| pos.1).max()?;
+        Some(Rect::new((top, left), (bottom - top + 1, right - left + 1)))
+    }
+}
+
+impl<T> Grid<T> {
+    /// Collects the positions reachable from `start` by orthogonal steps through
+    /// elements satisfying a predicate. The region is empty if `start` itself
+    /// is outside the grid or does not satisfy the predicate.
+    pub fn flood_fill<P>(&self, start: (usize, usize), mut predicate: P) -> Region
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let mut positions = BTreeSet::new();
+        if !self.get_pos(start).is_some_and(&mut predicate) {
+            return Region::new(positions, self);
+        }
+        let mut stack = vec![start];
+        positions.insert(start);
+        while let Some((row_index, col_index)) = stack.pop() {
+            for direction in Direction::ALL {
+                if let Some((next_i, next_j, element)) = self.walk(row_index, col_index, &direction)
+                {
+                    if !positions.contains(&(next_i, next_j)) && predicate(element) {
+                        positions.insert((next_i, next_j));
+                        stack.push((next_i, next_j));
+                    }
+                }
+            }
+        }
+        Region::new(positions, self)
+    }
+
+    /// Partitions the grid into regions of orthogonally connected elements
+    /// which are all equivalent under `eq` (which should behave as an
+    /// equivalence relation, such as `==` on some property of the elements).
+    ///
+    /// Regions are labelled in row-major order of their first positions.
+    pub fn components<E>(&self, mut eq: E) -> Vec<Region>
+    where
+        E: FnMut(&T, &T) -> bool,
+    {
+        let mut seen: BTreeSet<(usize, usize)> = BTreeSet::new();
+        let mut regions = vec![];
+        for (pos, first) in self.iter_cells() {
+            if seen.contains(&pos) {
+                continue;
+            }
+            let mut region = self.flood_fill(pos, |element| eq(first, element));
+            region.label = regions.len();
+            seen.extend(region.positions.iter().copied());
+            regions.push(region);
+        }
+        regions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_components() {
+        let grid: Grid<char> = "AAAA
+BBCD
+BBCC
+EEEC"
+            .parse()
+            .unwrap();
+        let regions = grid.components(|a, b| a == b);
+        let summary = regions
+            .iter()
+            .map(|region| (region.area(), region.perimeter(), region.sides()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![(4, 10, 4), (4, 8, 4), (4, 10, 8), (1, 4, 4), (3, 8, 4)]
+        );
+        assert_eq!(regions[2].label, 2);
+        assert_eq!(regions[2].bounding_box(), Some(Rect::new((1, 2), (3, 2))));
+    }
+
+    #[test]
+    fn check_wrapping_regions() {
+        let grid: Grid<char> = "A.A
+...
+A.A"
+        .parse()
+        .unwrap();
+        assert_eq!(grid.components(|a, b| a == b).len(), 5);
+        let grid = grid.with_topology(Topology::Wrapping);
+        let corners = grid.flood_fill((0, 0), |ch| *ch == 'A');
+        assert_eq!(
+            (corners.area(), corners.perimeter(), corners.sides()),
+            (4, 8, 4)
+        );
+
+        let grid: Grid<char> = "AAA
+...
+..."
+        .parse::<Grid<char>>()
+        .unwrap()
+        .with_topology(Topology::Wrapping);
+        let ring = grid.flood_fill((0, 0), |ch| *ch == 'A');
+        assert_eq!((ring.area(), ring.perimeter(), ring.sides()), (3, 6, 2));
+        let rest = grid.flood_fill((1, 1), |ch| *ch == '.');
+        assert_eq!((rest.area(), rest.perimeter(), rest.sides()), (6, 6, 2));
+    }
+
+    #[test]
+    fn check_flood_fill() {
+        let grid: Grid<char> = "..#
+.##
+#.."
+        .parse()
+        .unwrap();
+        assert_eq!(grid.flood_fill((0, 0), |ch| *ch == '.').area(), 3);
+        assert_eq!(grid.flood_fill((0, 2), |ch| *ch == '.').area(), 0);
+        assert_eq!(grid.flood_fill((0, 2), |ch| *ch == '#').area(), 3);
+    }
+}
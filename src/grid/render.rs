@@ -0,0 +1,146 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io::{stdout, IsTerminal},
+};
+
+use super::{Direction, Grid, GridCell};
+
+/// Foreground colours available for highlighting grid cells.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Colour {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Colour {
+    fn ansi_code(&self) -> u8 {
+        match self {
+            Self::Red => 31,
+            Self::Green => 32,
+            Self::Yellow => 33,
+            Self::Blue => 34,
+            Self::Magenta => 35,
+            Self::Cyan => 36,
+        }
+    }
+}
+
+/// Renders a [`Grid`] with some positions highlighted in colour and optionally
+/// a path drawn over it, as built by [`Grid::render`] and displayed with
+/// [`Display`].
+///
+/// By default colour is only used when stdout is a terminal, so output
+/// redirected to a file is plain text.
+pub struct Renderer<'a, T> {
+    grid: &'a Grid<T>,
+    overlay: HashMap<(usize, usize), (Option<char>, Colour)>,
+    colour: bool,
+}
+
+impl<T: Display> Grid<T> {
+    pub fn render(&self) -> Renderer<'_, T> {
+        Renderer {
+            grid: self,
+            overlay: HashMap::new(),
+            colour: stdout().is_terminal(),
+        }
+    }
+}
+
+/// The direction of a single orthogonal step between two positions, if they
+/// are adjacent.
+fn step_direction(from: (usize, usize), to: (usize, usize)) -> Option<Direction> {
+    Direction::ALL.into_iter().find(|direction| {
+        let delta = direction.delta();
+        from.0.checked_add_signed(delta.0) == Some(to.0)
+            && from.1.checked_add_signed(delta.1) == Some(to.1)
+    })
+}
+
+impl<T: Display> Renderer<'_, T> {
+    /// Colours the given positions, overriding any earlier highlight or path
+    /// at the same position.
+    pub fn highlight<I>(mut self, positions: I, colour: Colour) -> Self
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        for pos in positions {
+            self.overlay.insert(pos, (None, colour));
+        }
+        self
+    }
+
+    /// Draws a path over the grid, replacing each step with an arrow pointing
+    /// to the next position, and colours it. The final position keeps its
+    /// element, as do any steps between non-adjacent positions.
+    pub fn path(mut self, path: &[(usize, usize)], colour: Colour) -> Self {
+        for (index, pos) in path.iter().enumerate() {
+            let arrow = path
+                .get(index + 1)
+                .and_then(|next| step_direction(*pos, *next))
+                .map(|direction| direction.to_char());
+            self.overlay.insert(*pos, (arrow, colour));
+        }
+        self
+    }
+
+    /// Forces colour on or off, rather than detecting whether stdout is a terminal.
+    pub fn colour(mut self, colour: bool) -> Self {
+        self.colour = colour;
+        self
+    }
+}
+
+impl<T: Display> Display for Renderer<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (row_index, row) in self.grid.iter().enumerate() {
+            for (col_index, element) in row.iter().enumerate() {
+                match self.overlay.get(&(row_index, col_index)) {
+                    None => write!(f, "{element}")?,
+                    Some((arrow, colour)) => {
+                        let text = arrow.map_or_else(|| element.to_string(), String::from);
+                        if self.colour {
+                            write!(f, "\x1b[{}m{text}\x1b[0m", colour.ansi_code())?;
+                        } else {
+                            f.write_str(&text)?;
+                        }
+                    }
+                }
+            }
+            f.write_str("\n")?
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_plain_path() {
+        let grid: Grid<char> = "...\n...\n".parse().unwrap();
+        let rendered = grid
+            .render()
+            .path(&[(0, 0), (0, 1), (1, 1), (1, 0)], Colour::Red)
+            .colour(false)
+            .to_string();
+        assert_eq!(rendered, ">v.\n.<.\n");
+    }
+
+    #[test]
+    fn check_coloured_highlight() {
+        let grid: Grid<char> = "...\n...\n".parse().unwrap();
+        let rendered = grid
+            .render()
+            .highlight([(1, 2)], Colour::Green)
+            .colour(true)
+            .to_string();
+        assert_eq!(rendered, "...\n..\x1b[32m.\x1b[0m\n");
+    }
+}
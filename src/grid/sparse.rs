@@ -0,0 +1,185 @@
+use std::{collections::HashMap, fmt::Display};
+
+use super::{Direction, Grid};
+
+/// An unbounded grid with signed coordinates, storing only the cells that
+/// have been set. Every other cell reads as a default element.
+///
+/// Compare [`Grid`], which is dense and of fixed size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<(isize, isize), T>,
+    default: T,
+}
+
+impl<T: Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(((top, left), (bottom, right))) = self.bounds() else {
+            return Ok(());
+        };
+        for row_index in top..=bottom {
+            for col_index in left..=right {
+                f.write_str(&self.get_or_default(row_index, col_index).to_string())?;
+            }
+            f.write_str("\n")?
+        }
+        Ok(())
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        Self {
+            cells: HashMap::new(),
+            default,
+        }
+    }
+
+    /// Builds a sparse grid holding every cell of a dense one, at the same
+    /// (now signed) positions.
+    pub fn from_grid(grid: Grid<T>, default: T) -> Self {
+        let mut sparse = Self::new(default);
+        for (row_index, row) in grid.into_iter().enumerate() {
+            for (col_index, element) in row.into_iter().enumerate() {
+                sparse.insert(row_index as isize, col_index as isize, element);
+            }
+        }
+        sparse
+    }
+
+    /// Returns the element explicitly stored at a position, if any.
+    pub fn get(&self, row_index: isize, col_index: isize) -> Option<&T> {
+        self.cells.get(&(row_index, col_index))
+    }
+
+    pub fn get_pos(&self, pos: (isize, isize)) -> Option<&T> {
+        self.get(pos.0, pos.1)
+    }
+
+    /// Returns the element at a position, falling back to the default element
+    /// where nothing has been stored.
+    pub fn get_or_default(&self, row_index: isize, col_index: isize) -> &T {
+        self.get(row_index, col_index).unwrap_or(&self.default)
+    }
+
+    pub fn get_mut(&mut self, row_index: isize, col_index: isize) -> Option<&mut T> {
+        self.cells.get_mut(&(row_index, col_index))
+    }
+
+    /// Stores an element at a position, returning the one previously stored
+    /// there, if any.
+    pub fn insert(&mut self, row_index: isize, col_index: isize, element: T) -> Option<T> {
+        self.cells.insert((row_index, col_index), element)
+    }
+
+    /// Removes the element stored at a position, so that it reads as the
+    /// default again.
+    pub fn remove(&mut self, row_index: isize, col_index: isize) -> Option<T> {
+        self.cells.remove(&(row_index, col_index))
+    }
+
+    pub fn default_element(&self) -> &T {
+        &self.default
+    }
+
+    /// The number of explicitly stored cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The top-left and bottom-right corners (inclusive) of the smallest
+    /// rectangle containing every stored cell, or `None` if there are none.
+    pub fn bounds(&self) -> Option<((isize, isize), (isize, isize))> {
+        let top = self.cells.keys().map(|pos| pos.0).min()?;
+        let bottom = self.cells.keys().map(|pos| pos.0).max()?;
+        let left = self.cells.keys().map(|pos| pos.1).min()?;
+        let right = self.cells.keys().map(|pos| pos.1).max()?;
+        Some(((top, left), (bottom, right)))
+    }
+
+    /// Iterates over the stored cells with their positions, in no particular order.
+    pub fn iter_cells(&self) -> impl Iterator<Item = ((isize, isize), &T)> {
+        self.cells.iter().map(|(pos, element)| (*pos, element))
+    }
+
+    /// Searches for the first (row-major ordering) stored element that
+    /// satisfies a predicate, and return it with its location if found.
+    ///
+    /// See [`Grid::locate`].
+    pub fn locate<P>(&self, predicate: P) -> Option<(isize, isize, &T)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.locate_all(predicate).into_iter().next()
+    }
+
+    /// Return all stored elements (with their positions, in row-major order)
+    /// that satisfy a predicate.
+    ///
+    /// See [`Grid::locate_all`].
+    pub fn locate_all<P>(&self, mut predicate: P) -> Vec<(isize, isize, &T)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let mut found = self
+            .cells
+            .iter()
+            .filter(|(_, element)| predicate(element))
+            .map(|(pos, element)| (pos.0, pos.1, element))
+            .collect::<Vec<_>>();
+        found.sort_unstable_by_key(|(row_index, col_index, _)| (*row_index, *col_index));
+        found
+    }
+
+    /// Steps one cell in a direction. As the grid is unbounded this always
+    /// succeeds, reading the default element if nothing is stored there.
+    ///
+    /// See [`Grid::walk`].
+    pub fn walk(
+        &self,
+        row_index: isize,
+        col_index: isize,
+        direction: &Direction,
+    ) -> (isize, isize, &T) {
+        let (next_i, next_j) = match direction {
+            Direction::Up => (row_index - 1, col_index),
+            Direction::Down => (row_index + 1, col_index),
+            Direction::Left => (row_index, col_index - 1),
+            Direction::Right => (row_index, col_index + 1),
+        };
+        (next_i, next_j, self.get_or_default(next_i, next_j))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_bounds_and_display() {
+        let mut grid = SparseGrid::new('.');
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.to_string(), "");
+        grid.insert(-1, -2, '#');
+        grid.insert(1, 0, '#');
+        assert_eq!(grid.bounds(), Some(((-1, -2), (1, 0))));
+        assert_eq!(grid.to_string(), "#..\n...\n..#\n");
+    }
+
+    #[test]
+    fn check_walk_and_locate() {
+        let dense: Grid<char> = ".#\n#.".parse().unwrap();
+        let grid = SparseGrid::from_grid(dense, '.');
+        assert_eq!(grid.walk(0, 0, &Direction::Up), (-1, 0, &'.'));
+        assert_eq!(grid.walk(0, 0, &Direction::Right), (0, 1, &'#'));
+        assert_eq!(
+            grid.locate_all(|ch| *ch == '#'),
+            vec![(0, 1, &'#'), (1, 0, &'#')]
+        );
+        assert_eq!(grid.locate(|ch| *ch == '#'), Some((0, 1, &'#')));
+    }
+}
@@ -0,0 +1,164 @@
+use super::Grid;
+
+/// An axis-aligned rectangle of grid positions, given by its top-left corner
+/// and its (rows, columns) dimensions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub origin: (usize, usize),
+    pub dimensions: (usize, usize),
+}
+
+impl Rect {
+    pub fn new(origin: (usize, usize), dimensions: (usize, usize)) -> Self {
+        Self { origin, dimensions }
+    }
+
+    /// Checks whether a position lies within the rectangle.
+    pub fn contains(&self, pos: (usize, usize)) -> bool {
+        (self.origin.0..self.origin.0 + self.dimensions.0).contains(&pos.0)
+            && (self.origin.1..self.origin.1 + self.dimensions.1).contains(&pos.1)
+    }
+}
+
+// All transforms keep the grid's topology.
+
+impl<T> Grid<T> {
+    /// Builds a new grid by applying a function to every element, keeping
+    /// the layout unchanged.
+    ///
+    /// Compare [`Iterator::map`].
+    pub fn map<U, F>(&self, mut f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid(
+            self.iter()
+                .map(|row| row.iter().map(&mut f).collect())
+                .collect(),
+            self.1,
+        )
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Swaps rows and columns, reflecting the grid in its leading diagonal.
+    pub fn transpose(&self) -> Self {
+        let (rows, cols) = self.dimensions();
+        Self(
+            (0..cols)
+                .map(|col_index| {
+                    (0..rows)
+                        .map(|row_index| self.0[row_index][col_index].clone())
+                        .collect()
+                })
+                .collect(),
+            self.1,
+        )
+    }
+
+    /// Rotates the grid by 90 degrees clockwise.
+    pub fn rotate_cw(&self) -> Self {
+        self.transpose().flip_horizontal()
+    }
+
+    /// Rotates the grid by 90 degrees anticlockwise.
+    pub fn rotate_ccw(&self) -> Self {
+        self.transpose().flip_vertical()
+    }
+
+    /// Mirrors the grid left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        Self(
+            self.iter()
+                .map(|row| row.iter().rev().cloned().collect())
+                .collect(),
+            self.1,
+        )
+    }
+
+    /// Mirrors the grid top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        Self(self.iter().rev().cloned().collect(), self.1)
+    }
+
+    /// Copies out the region of the grid covered by a rectangle, or returns
+    /// `None` if the rectangle does not lie entirely within the grid.
+    pub fn sub_grid(&self, rect: Rect) -> Option<Self> {
+        let (rows, cols) = self.dimensions();
+        if rect.origin.0 + rect.dimensions.0 > rows || rect.origin.1 + rect.dimensions.1 > cols {
+            return None;
+        }
+        Some(Self(
+            self.0[rect.origin.0..rect.origin.0 + rect.dimensions.0]
+                .iter()
+                .map(|row| row[rect.origin.1..rect.origin.1 + rect.dimensions.1].to_vec())
+                .collect(),
+            self.1,
+        ))
+    }
+
+    /// Surrounds the grid with a border `n` elements thick on every side.
+    pub fn pad(&self, n: usize, fill: T) -> Self {
+        let (_, cols) = self.dimensions();
+        let border_row = vec![fill.clone(); cols + 2 * n];
+        let border = vec![fill.clone(); n];
+        let mut padded = vec![border_row.clone(); n];
+        padded.extend(
+            self.iter()
+                .map(|row| [border.as_slice(), row, border.as_slice()].concat()),
+        );
+        padded.extend(vec![border_row; n]);
+        Self(padded, self.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_transpose_and_rotations() {
+        let grid: Grid<u8> = "123
+456"
+        .parse()
+        .unwrap();
+        assert_eq!(grid.transpose().to_string(), "14\n25\n36\n");
+        assert_eq!(grid.rotate_cw().to_string(), "41\n52\n63\n");
+        assert_eq!(grid.rotate_ccw().to_string(), "36\n25\n14\n");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+    }
+
+    #[test]
+    fn check_flips() {
+        let grid: Grid<u8> = "123
+456"
+        .parse()
+        .unwrap();
+        assert_eq!(grid.flip_horizontal().to_string(), "321\n654\n");
+        assert_eq!(grid.flip_vertical().to_string(), "456\n123\n");
+    }
+
+    #[test]
+    fn check_sub_grid_and_pad() {
+        let grid: Grid<u8> = "123
+456"
+        .parse()
+        .unwrap();
+        assert_eq!(
+            grid.sub_grid(Rect::new((0, 1), (2, 2)))
+                .map(|sub| sub.to_string()),
+            Some("23\n56\n".to_string())
+        );
+        assert_eq!(grid.sub_grid(Rect::new((1, 1), (2, 2))), None);
+        assert_eq!(grid.pad(1, 0).to_string(), "00000\n01230\n04560\n00000\n");
+    }
+
+    #[test]
+    fn check_map() {
+        let grid: Grid<u8> = "123
+456"
+        .parse()
+        .unwrap();
+        assert_eq!(grid.map(|el| el % 2 == 0).locate_all(|even| *even).len(), 3);
+    }
+}
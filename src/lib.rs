@@ -1,5 +1,11 @@
 use std::process::Command;
 
+use simulation::{play, Playback};
+
+// Lets derive macros refer to this crate by name from inside it too.
+extern crate self as advent_of_code_template;
+
+pub mod cycle;
 pub mod day00;
 pub mod day01;
 pub mod day02;
@@ -26,6 +32,8 @@ pub mod day22;
 pub mod day23;
 pub mod day24;
 pub mod day25;
+pub mod grid;
+pub mod simulation;
 
 pub trait Solution {
     type ParsedInput;
@@ -118,3 +126,26 @@ pub fn bench_day(day: &i32) {
         .expect("Failed to run benchmark");
     println!("{}", String::from_utf8(result.stdout).unwrap());
 }
+
+pub fn explain_day(day: &i32) {
+    match day {
+        5 => print!("{}", day05::explain(include_str!("../inputs/5"))),
+        7 => print!("{}", day07::explain(include_str!("../inputs/7"))),
+        _ => println!("Day {} has no explanation", day),
+    }
+}
+
+pub fn visualise_day(day: &i32, playback: &Playback) {
+    let steps = match day {
+        6 => play(
+            &mut day06::Patrol::new(day06::Day06::parse_input(include_str!("../inputs/6"))),
+            playback,
+        ),
+        _ => {
+            println!("Day {} has no visualisation", day);
+            return;
+        }
+    }
+    .expect("Failed to play visualisation");
+    println!("Day {} finished after {} steps", day, steps);
+}
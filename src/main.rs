@@ -1,4 +1,8 @@
-use advent_of_code_template::{bench_day, solve_day};
+use std::path::PathBuf;
+
+use advent_of_code_template::{
+    bench_day, explain_day, simulation::Playback, solve_day, visualise_day,
+};
 use clap::Parser;
 
 #[derive(Parser)]
@@ -10,6 +14,39 @@ struct Cli {
     /// Benchmarks the solution for given days.
     #[arg(short, long)]
     bench: bool,
+
+    /// Plays the solution's simulation frame by frame, for days which have one.
+    #[arg(short, long, conflicts_with = "bench")]
+    visualise: bool,
+
+    /// Explains how the solution reached its answers, for days which support it.
+    #[arg(short, long, conflicts_with_all = ["bench", "visualise"])]
+    explain: bool,
+
+    /// Frames per second when visualising in the terminal.
+    #[arg(long, default_value_t = 10.0, requires = "visualise", value_parser = positive_rate)]
+    fps: f64,
+
+    /// Writes the visualisation frames to this file instead of the terminal.
+    #[arg(long, requires = "visualise")]
+    frames_file: Option<PathBuf>,
+
+    /// Saves the visualisation frames as PNG images in this directory instead of
+    /// showing them in the terminal.
+    #[arg(long, requires = "visualise", conflicts_with = "frames_file")]
+    frames_dir: Option<PathBuf>,
+}
+
+/// Parses a frame rate, which must be a positive, finite number.
+fn positive_rate(value: &str) -> Result<f64, String> {
+    let rate: f64 = value
+        .parse()
+        .map_err(|_| format!("{value:?} is not a number"))?;
+    if rate.is_finite() && rate > 0.0 {
+        Ok(rate)
+    } else {
+        Err(format!("{value} is not a positive frame rate"))
+    }
 }
 
 fn main() {
@@ -24,8 +61,19 @@ fn main() {
     } else {
         days_to_execute = days.drain(1..).collect(); // Skip day0 example
     }
+    let playback = match (cli.frames_file, cli.frames_dir) {
+        (Some(path), _) => Playback::File(path),
+        (_, Some(directory)) => Playback::Images(directory),
+        (None, None) => Playback::Terminal {
+            frames_per_second: cli.fps,
+        },
+    };
     for day in days_to_execute {
-        if cli.bench {
+        if cli.explain {
+            explain_day(&day);
+        } else if cli.visualise {
+            visualise_day(&day, &playback);
+        } else if cli.bench {
             bench_day(&day);
         } else {
             solve_day(&day, true);
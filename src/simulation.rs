@@ -0,0 +1,140 @@
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    thread::sleep,
+    time::Duration,
+};
+
+use crate::grid::{FrameWriter, Grid, Image};
+
+/// A puzzle whose state evolves step by step on a grid, which can be played
+/// back frame by frame with [`play`].
+pub trait Simulation {
+    type Cell: Display;
+
+    /// Advances the simulation by one step, returning `false` (and leaving the
+    /// state unchanged) once it has finished.
+    fn step(&mut self) -> bool;
+
+    /// The grid holding the current state.
+    fn grid(&self) -> &Grid<Self::Cell>;
+
+    /// Draws the current state as one frame. Override this to overlay extra
+    /// information, e.g. with [`Grid::render`].
+    fn render(&self) -> String {
+        self.grid().to_string()
+    }
+
+    /// Draws the current state as an image, for simulations which support it.
+    fn draw(&self) -> Option<Image> {
+        None
+    }
+}
+
+/// Where to send the frames of a [`Simulation`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Playback {
+    /// Redraw each frame in the terminal, at the given rate.
+    Terminal { frames_per_second: f64 },
+    /// Write every frame to a text file for later replay.
+    File(PathBuf),
+    /// Save every frame as a numbered PNG image in a directory.
+    Images(PathBuf),
+}
+
+/// Runs a simulation to completion, showing each frame (including the initial
+/// state) as requested, and returns the number of steps taken.
+pub fn play<S: Simulation>(simulation: &mut S, playback: &Playback) -> io::Result<usize> {
+    match playback {
+        Playback::Terminal { frames_per_second } => {
+            let delay = Duration::from_secs_f64(1.0 / frames_per_second);
+            let mut stdout = io::stdout().lock();
+            let mut steps = 0;
+            loop {
+                // Clear the screen and move the cursor home before each frame
+                write!(stdout, "\x1b[2J\x1b[H{}", simulation.render())?;
+                writeln!(stdout, "Step {steps}")?;
+                stdout.flush()?;
+                if !simulation.step() {
+                    return Ok(steps);
+                }
+                steps += 1;
+                sleep(delay);
+            }
+        }
+        Playback::File(path) => write_frames(simulation, BufWriter::new(File::create(path)?)),
+        Playback::Images(directory) => write_images(simulation, &mut FrameWriter::new(directory)?),
+    }
+}
+
+/// Runs a simulation to completion, writing each frame (including the initial
+/// state) under a numbered header, and returns the number of steps taken.
+pub fn write_frames<S: Simulation, W: Write>(simulation: &mut S, mut out: W) -> io::Result<usize> {
+    let mut steps = 0;
+    loop {
+        writeln!(out, "Step {steps}")?;
+        write!(out, "{}", simulation.render())?;
+        if !simulation.step() {
+            out.flush()?;
+            return Ok(steps);
+        }
+        steps += 1;
+    }
+}
+
+/// Runs a simulation to completion, saving each frame (including the initial
+/// state) as an image, and returns the number of steps taken.
+pub fn write_images<S: Simulation>(simulation: &mut S, out: &mut FrameWriter) -> io::Result<usize> {
+    let mut steps = 0;
+    loop {
+        let image = simulation.draw().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                "This simulation cannot be drawn as an image",
+            )
+        })?;
+        out.write(&image)?;
+        if !simulation.step() {
+            return Ok(steps);
+        }
+        steps += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shifts a marker right along a single row until it reaches the end.
+    struct Slider(Grid<char>, usize);
+
+    impl Simulation for Slider {
+        type Cell = char;
+
+        fn step(&mut self) -> bool {
+            if self.0.get(0, self.1 + 1).is_none() {
+                return false;
+            }
+            self.0.swap((0, self.1), (0, self.1 + 1));
+            self.1 += 1;
+            true
+        }
+
+        fn grid(&self) -> &Grid<char> {
+            &self.0
+        }
+    }
+
+    #[test]
+    fn check_write_frames() {
+        let mut slider = Slider("o..".parse().unwrap(), 0);
+        let mut out = vec![];
+        assert_eq!(write_frames(&mut slider, &mut out).unwrap(), 2);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Step 0\no..\nStep 1\n.o.\nStep 2\n..o\n"
+        );
+    }
+}